@@ -0,0 +1,172 @@
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+use crate::api::stt::{self, SttProvider, SttRequest, Transcript};
+use crate::api::{self, HTTP_CLIENT};
+use crate::audio::encoder::EncodedAudio;
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::models::{Segment, WordTiming};
+
+const DEEPGRAM_BASE_URL: &str = "https://api.deepgram.com/v1";
+pub const DEFAULT_MODEL: &str = "nova-2";
+
+#[derive(Debug, Deserialize)]
+struct ListenResponse {
+    results: ListenResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenResults {
+    channels: Vec<ListenChannel>,
+    /// Present when the request used `utterances`.
+    #[serde(default)]
+    utterances: Vec<Utterance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Utterance {
+    start: f64,
+    end: f64,
+    confidence: f64,
+    transcript: String,
+    #[serde(default)]
+    words: Vec<ListenWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenWord {
+    word: String,
+    /// The word with casing and punctuation, when `smart_format` is on.
+    punctuated_word: Option<String>,
+    start: f64,
+    end: f64,
+    confidence: f64,
+}
+
+impl From<Utterance> for Segment {
+    fn from(u: Utterance) -> Self {
+        Segment {
+            start_ms: stt::secs_to_ms(u.start),
+            end_ms: stt::secs_to_ms(u.end),
+            text: u.transcript.trim().to_string(),
+            confidence: Some(u.confidence),
+            words: u
+                .words
+                .into_iter()
+                .map(|w| WordTiming {
+                    start_ms: stt::secs_to_ms(w.start),
+                    end_ms: stt::secs_to_ms(w.end),
+                    word: w.punctuated_word.unwrap_or(w.word),
+                    confidence: Some(w.confidence),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenChannel {
+    alternatives: Vec<ListenAlternative>,
+    /// Present when the request used `detect_language`.
+    detected_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenAlternative {
+    transcript: String,
+}
+
+/// Transcribe encoded audio using Deepgram's pre-recorded `/listen` API.
+pub async fn transcribe(
+    api_key: &str,
+    audio: EncodedAudio,
+    request: &SttRequest,
+) -> Result<Transcript> {
+    // Nova-3 replaced keyword boosting with key term prompting
+    let vocabulary_param = if request.model.starts_with("nova-3") {
+        "keyterm"
+    } else {
+        "keywords"
+    };
+    let mut query = vec![("model", request.model.as_str()), ("smart_format", "true")];
+    // Utterances are Deepgram's timed segments
+    if request.timings {
+        query.push(("utterances", "true"));
+    }
+    if request.detect_language() {
+        query.push(("detect_language", "true"));
+    } else {
+        query.push(("language", request.language.as_str()));
+    }
+    query.extend(
+        request
+            .vocabulary
+            .iter()
+            .map(|term| (vocabulary_param, term.as_str())),
+    );
+
+    let resp = HTTP_CLIENT
+        .post(format!("{}/listen", DEEPGRAM_BASE_URL))
+        .query(&query)
+        .timeout(api::stt_timeout(audio.duration))
+        .header("Authorization", format!("Token {}", api_key))
+        .header("Content-Type", audio.mime_type)
+        .body(audio.data)
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(VoiceFlowError::Api(format!(
+            "Deepgram STT error {}: {}",
+            status, body
+        )));
+    }
+
+    let listen: ListenResponse = resp.json().await?;
+    let channel = listen.results.channels.first();
+    let transcript = channel
+        .and_then(|c| c.alternatives.first())
+        .map(|a| a.transcript.clone())
+        .unwrap_or_default();
+    let language = channel
+        .and_then(|c| c.detected_language.as_deref())
+        .map(stt::language_code);
+
+    Ok(Transcript {
+        text: transcript.trim().to_string(),
+        language,
+        segments: listen
+            .results
+            .utterances
+            .into_iter()
+            .map(Segment::from)
+            .collect(),
+    })
+}
+
+/// Deepgram as an STT backend.
+pub struct DeepgramStt {
+    api_key: String,
+}
+
+impl DeepgramStt {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+impl SttProvider for DeepgramStt {
+    fn name(&self) -> &'static str {
+        "Deepgram"
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: EncodedAudio,
+        request: &'a SttRequest,
+    ) -> BoxFuture<'a, Result<Transcript>> {
+        Box::pin(transcribe(&self.api_key, audio, request))
+    }
+}
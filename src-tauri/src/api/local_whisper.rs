@@ -0,0 +1,169 @@
+//! Offline transcription with whisper.cpp (cargo feature `local-whisper`).
+
+use futures_util::future::BoxFuture;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use crate::api::stt::{SttProvider, SttRequest, Transcript, AUTO_LANGUAGE};
+use crate::audio::encoder::{EncodedAudio, UploadFormat};
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::models::Segment;
+
+/// Loaded model, kept across recordings (loading a GGML file takes seconds).
+/// Holding the lock during inference also serializes chunks: one whisper.cpp run
+/// already uses every core.
+static MODEL: Mutex<Option<(String, Arc<WhisperContext>)>> = Mutex::new(None);
+
+/// Upper bound on inference threads; whisper.cpp gains little beyond this.
+const MAX_THREADS: usize = 8;
+
+/// whisper.cpp running on the CPU, with a GGML model file from disk.
+pub struct LocalWhisper {
+    model_path: String,
+}
+
+impl LocalWhisper {
+    pub fn new(model_path: String) -> Self {
+        Self { model_path }
+    }
+}
+
+impl SttProvider for LocalWhisper {
+    fn name(&self) -> &'static str {
+        "Local Whisper"
+    }
+
+    fn preferred_format(&self) -> Option<UploadFormat> {
+        // 16 kHz WAV decodes straight back into the samples whisper.cpp expects
+        Some(UploadFormat::Wav)
+    }
+
+    fn supports_translation(&self, _model: &str) -> bool {
+        true
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: EncodedAudio,
+        request: &'a SttRequest,
+    ) -> BoxFuture<'a, Result<Transcript>> {
+        let model_path = self.model_path.clone();
+        let language = request.language.clone();
+        let prompt = request.whisper_prompt();
+        let translate = request.translate;
+        Box::pin(async move {
+            tauri::async_runtime::spawn_blocking(move || {
+                transcribe_blocking(
+                    &model_path,
+                    &audio.data,
+                    &language,
+                    prompt.as_deref(),
+                    translate,
+                )
+            })
+            .await
+            .map_err(|e| VoiceFlowError::Api(format!("Local Whisper task failed: {}", e)))?
+        })
+    }
+}
+
+fn transcribe_blocking(
+    model_path: &str,
+    wav: &[u8],
+    language: &str,
+    prompt: Option<&str>,
+    translate: bool,
+) -> Result<Transcript> {
+    let samples = decode_wav(wav)?;
+
+    let mut model = MODEL
+        .lock()
+        .map_err(|e| VoiceFlowError::Api(format!("Whisper model lock poisoned: {}", e)))?;
+    let ctx = match model.as_ref() {
+        Some((path, ctx)) if path == model_path => ctx.clone(),
+        _ => {
+            log::info!("Loading Whisper model from {}", model_path);
+            let ctx =
+                WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+                    .map_err(|e| {
+                        VoiceFlowError::Api(format!(
+                            "Failed to load Whisper model {}: {}",
+                            model_path, e
+                        ))
+                    })?;
+            let ctx = Arc::new(ctx);
+            *model = Some((model_path.to_string(), ctx.clone()));
+            ctx
+        }
+    };
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(MAX_THREADS);
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    // whisper.cpp detects the language itself when given "auto"
+    params.set_language(Some(language));
+    params.set_translate(translate);
+    params.set_n_threads(threads as i32);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    if let Some(prompt) = prompt {
+        params.set_initial_prompt(prompt);
+    }
+
+    let mut state = ctx.create_state().map_err(whisper_error)?;
+    state.full(params, &samples).map_err(whisper_error)?;
+
+    let count = state.full_n_segments().map_err(whisper_error)?;
+    let mut text = String::new();
+    let mut segments = Vec::new();
+    for i in 0..count {
+        let segment_text = state
+            .full_get_segment_text_lossy(i)
+            .map_err(whisper_error)?;
+        text.push_str(&segment_text);
+        // whisper.cpp times segments in centiseconds
+        let t0 = state.full_get_segment_t0(i).map_err(whisper_error)?;
+        let t1 = state.full_get_segment_t1(i).map_err(whisper_error)?;
+        segments.push(Segment {
+            start_ms: t0.max(0) as u64 * 10,
+            end_ms: t1.max(0) as u64 * 10,
+            text: segment_text.trim().to_string(),
+            confidence: None,
+            words: Vec::new(),
+        });
+    }
+    let detected = if language == AUTO_LANGUAGE {
+        state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .map(str::to_string)
+    } else {
+        None
+    };
+    Ok(Transcript {
+        text: text.trim().to_string(),
+        language: detected,
+        segments,
+    })
+}
+
+/// Decode the 16-bit mono WAV produced by `encoder` back into f32 samples.
+fn decode_wav(wav: &[u8]) -> Result<Vec<f32>> {
+    let reader = hound::WavReader::new(Cursor::new(wav))
+        .map_err(|e| VoiceFlowError::Audio(e.to_string()))?;
+    reader
+        .into_samples::<i16>()
+        .map(|s| s.map(|s| s as f32 / 32768.0))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| VoiceFlowError::Audio(e.to_string()))
+}
+
+fn whisper_error(e: whisper_rs::WhisperError) -> VoiceFlowError {
+    VoiceFlowError::Api(format!("Local Whisper error: {}", e))
+}
@@ -1 +1,44 @@
+pub mod deepgram;
 pub mod groq;
+#[cfg(feature = "local-whisper")]
+pub mod local_whisper;
+pub mod ollama;
+pub mod openai;
+pub mod refine;
+pub mod stt;
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Extra time an STT request gets per second of audio. A 5-minute chunk is ~10 MB
+/// as WAV, which takes well over `REQUEST_TIMEOUT` to upload on many connections.
+const STT_TIMEOUT_PER_AUDIO_SEC: f64 = 0.5;
+
+/// Shared HTTP client with connection pooling and timeout.
+pub(crate) static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// Timeout for uploading and transcribing `audio` seconds of speech, replacing the
+/// client's default for STT requests.
+pub(crate) fn stt_timeout(audio: Duration) -> Duration {
+    REQUEST_TIMEOUT + audio.mul_f64(STT_TIMEOUT_PER_AUDIO_SEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stt_timeout_grows_with_audio() {
+        assert_eq!(stt_timeout(Duration::ZERO), REQUEST_TIMEOUT);
+        assert_eq!(
+            stt_timeout(Duration::from_secs(300)),
+            Duration::from_secs(180)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::api::refine::{ChatRequest, RefineProvider};
+use crate::api::HTTP_CLIENT;
+use crate::errors::{Result, VoiceFlowError};
+
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+pub const DEFAULT_MODEL: &str = "llama3.2";
+/// Local models can be slow, especially on first load; allow more than the shared client's timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_TOKENS: u32 = 2048;
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Run a chat completion using Ollama's native `/api/chat` endpoint.
+pub async fn chat(base_url: &str, request: &ChatRequest) -> Result<String> {
+    let payload = serde_json::json!({
+        "model": request.model,
+        "messages": [
+            {
+                "role": "system",
+                "content": request.system_prompt
+            },
+            {
+                "role": "user",
+                "content": request.user_text
+            }
+        ],
+        "stream": false,
+        "options": {
+            "temperature": request.temperature,
+            "num_predict": MAX_TOKENS
+        }
+    });
+
+    let resp = HTTP_CLIENT
+        .post(format!("{}/api/chat", base_url.trim_end_matches('/')))
+        .timeout(REQUEST_TIMEOUT)
+        .json(&payload)
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(VoiceFlowError::Api(format!(
+            "Ollama error {}: {}",
+            status, body
+        )));
+    }
+
+    let chat_resp: ChatResponse = resp.json().await?;
+    Ok(chat_resp.message.content.trim().to_string())
+}
+
+/// A local Ollama server as a refinement backend; no API key involved.
+pub struct Ollama {
+    base_url: String,
+}
+
+impl Ollama {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+}
+
+impl RefineProvider for Ollama {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn complete<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(chat(&self.base_url, request))
+    }
+}
@@ -0,0 +1,440 @@
+use futures_util::future::BoxFuture;
+use reqwest::{multipart, Response, StatusCode};
+use serde::Deserialize;
+
+use crate::api::refine::{ChatRequest, RefineProvider};
+use crate::api::stt::{self, SttProvider, SttRequest, Transcript};
+use crate::api::{self, HTTP_CLIENT};
+use crate::audio::encoder::EncodedAudio;
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::models::{Segment, WordTiming};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+pub const DEFAULT_STT_MODEL: &str = "whisper-1";
+pub const DEFAULT_CHAT_MODEL: &str = "gpt-4o-mini";
+const MAX_TOKENS: u32 = 2048;
+
+/// `verbose_json` transcription response; only the fields used are parsed.
+#[derive(Debug, Deserialize)]
+struct VerboseTranscription {
+    text: String,
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<VerboseSegment>,
+    /// Only present when word timestamps were requested.
+    #[serde(default)]
+    words: Vec<VerboseWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    avg_logprob: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+impl VerboseTranscription {
+    fn into_segments(self) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = self
+            .segments
+            .into_iter()
+            .map(|s| Segment {
+                start_ms: stt::secs_to_ms(s.start),
+                end_ms: stt::secs_to_ms(s.end),
+                text: s.text.trim().to_string(),
+                // Mean token log-probability, as a probability
+                confidence: s.avg_logprob.map(f64::exp),
+                words: Vec::new(),
+            })
+            .collect();
+        let words = self
+            .words
+            .into_iter()
+            .map(|w| WordTiming {
+                start_ms: stt::secs_to_ms(w.start),
+                end_ms: stt::secs_to_ms(w.end),
+                word: w.word.trim().to_string(),
+                confidence: None,
+            })
+            .collect();
+        stt::attach_words(&mut segments, words);
+        segments
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Call an OpenAI-style `/audio/transcriptions` endpoint, or `/audio/translations` when
+/// the request asks for English output. Shared by every backend that speaks this
+/// protocol (OpenAI, Groq, faster-whisper-server, LocalAI, ...).
+/// An empty `api_key` sends no `Authorization` header, for unauthenticated local servers.
+pub async fn transcribe(
+    base_url: &str,
+    provider: &str,
+    api_key: &str,
+    audio: EncodedAudio,
+    request: &SttRequest,
+) -> Result<Transcript> {
+    let verbose = wants_verbose(request);
+    send_transcription(base_url, provider, api_key, &audio, request, verbose).await
+}
+
+/// One `/audio/*` request, asking for `verbose_json` or plain text.
+async fn send_transcription(
+    base_url: &str,
+    provider: &str,
+    api_key: &str,
+    audio: &EncodedAudio,
+    request: &SttRequest,
+    verbose: bool,
+) -> Result<Transcript> {
+    let resp = post_transcription(base_url, api_key, audio, request, verbose).await?;
+    read_transcription(provider, resp, request, verbose).await
+}
+
+/// Upload the audio, returning the response whatever its status.
+async fn post_transcription(
+    base_url: &str,
+    api_key: &str,
+    audio: &EncodedAudio,
+    request: &SttRequest,
+    verbose: bool,
+) -> Result<Response> {
+    let file_part = multipart::Part::bytes(audio.data.clone())
+        .file_name(audio.file_name)
+        .mime_str(audio.mime_type)
+        .map_err(|e| VoiceFlowError::Api(e.to_string()))?;
+
+    let mut form = multipart::Form::new()
+        .part("file", file_part)
+        .text("model", request.model.clone());
+    form = form.text(
+        "response_format",
+        if verbose { "verbose_json" } else { "text" }.to_string(),
+    );
+    // The translations endpoint always outputs English, takes no language and only
+    // times segments
+    let endpoint = if request.translate {
+        "translations"
+    } else {
+        if !request.detect_language() {
+            form = form.text("language", request.language.clone());
+        }
+        if verbose && request.timings {
+            form = form
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        }
+        "transcriptions"
+    };
+    if let Some(prompt) = request.whisper_prompt() {
+        form = form.text("prompt", prompt);
+    }
+
+    let mut req = HTTP_CLIENT
+        .post(format!(
+            "{}/audio/{}",
+            base_url.trim_end_matches('/'),
+            endpoint
+        ))
+        .timeout(api::stt_timeout(audio.duration))
+        .multipart(form);
+    if !api_key.is_empty() {
+        req = req.header("Authorization", format!("Bearer {}", api_key));
+    }
+    Ok(req.send().await?)
+}
+
+/// Parse a transcription response in the format `verbose` asked for.
+async fn read_transcription(
+    provider: &str,
+    resp: Response,
+    request: &SttRequest,
+    verbose: bool,
+) -> Result<Transcript> {
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(VoiceFlowError::Api(format!(
+            "{} STT error {}: {}",
+            provider, status, body
+        )));
+    }
+
+    if verbose {
+        let body: VerboseTranscription = resp.json().await?;
+        let text = body.text.trim().to_string();
+        let language = body
+            .language
+            .as_deref()
+            .filter(|_| request.detect_language() && !request.translate)
+            .map(stt::language_code);
+        return Ok(Transcript {
+            text,
+            language,
+            segments: body.into_segments(),
+        });
+    }
+    let text = resp.text().await?;
+    Ok(Transcript::text(text.trim()))
+}
+
+/// Whether to ask for `verbose_json`, which carries the segment timings and, without
+/// a language, the one Whisper detected. Plain text is quicker and the only format
+/// OpenAI's GPT-4o transcription models answer in.
+fn wants_verbose(request: &SttRequest) -> bool {
+    let detect = request.detect_language() && !request.translate;
+    (request.timings || detect) && supports_verbose(&request.model)
+}
+
+/// Whether a failed `verbose_json` request is worth repeating as plain text: the
+/// server refused the request's options, not the key, the audio or its own load.
+fn rejects_verbose(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_REQUEST
+            | StatusCode::UNSUPPORTED_MEDIA_TYPE
+            | StatusCode::UNPROCESSABLE_ENTITY
+    )
+}
+
+/// Whether `model` can answer with `verbose_json`. Every Whisper deployment can;
+/// OpenAI's GPT-4o transcription models cannot.
+fn supports_verbose(model: &str) -> bool {
+    !model.starts_with("gpt-4o")
+}
+
+/// Call an OpenAI-style `/chat/completions` endpoint (OpenAI, Groq, llama.cpp server,
+/// vLLM, LM Studio, ...). An empty `api_key` sends no `Authorization` header.
+pub async fn chat(
+    base_url: &str,
+    provider: &str,
+    api_key: &str,
+    request: &ChatRequest,
+) -> Result<String> {
+    let payload = serde_json::json!({
+        "model": request.model,
+        "messages": [
+            {
+                "role": "system",
+                "content": request.system_prompt
+            },
+            {
+                "role": "user",
+                "content": request.user_text
+            }
+        ],
+        "temperature": request.temperature,
+        "max_tokens": MAX_TOKENS
+    });
+
+    let mut req = HTTP_CLIENT
+        .post(format!(
+            "{}/chat/completions",
+            base_url.trim_end_matches('/')
+        ))
+        .json(&payload);
+    if !api_key.is_empty() {
+        req = req.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let resp = req.send().await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(VoiceFlowError::Api(format!(
+            "{} LLM error {}: {}",
+            provider, status, body
+        )));
+    }
+
+    let chat_resp: ChatResponse = resp.json().await?;
+    let content = chat_resp
+        .choices
+        .first()
+        .map(|c| c.message.content.clone())
+        .unwrap_or_default();
+
+    Ok(content.trim().to_string())
+}
+
+/// Any OpenAI-compatible transcription server, addressed by base URL
+/// (e.g. `http://localhost:8000/v1` for a self-hosted faster-whisper server).
+pub struct OpenAiStt {
+    base_url: String,
+    api_key: String,
+}
+
+impl OpenAiStt {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self { base_url, api_key }
+    }
+}
+
+impl SttProvider for OpenAiStt {
+    fn name(&self) -> &'static str {
+        "OpenAI-compatible"
+    }
+
+    fn supports_translation(&self, model: &str) -> bool {
+        // `/audio/translations` only serves Whisper; OpenAI rejects the GPT-4o
+        // transcription models there
+        !model.starts_with("gpt-4o")
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: EncodedAudio,
+        request: &'a SttRequest,
+    ) -> BoxFuture<'a, Result<Transcript>> {
+        Box::pin(async move {
+            let (url, name, key) = (&self.base_url, self.name(), &self.api_key);
+            let verbose = wants_verbose(request);
+            let resp = post_transcription(url, key, &audio, request, verbose).await?;
+            // Self-hosted servers may not know `verbose_json` or the timestamp
+            // options; the text alone is better than no transcript
+            if verbose && rejects_verbose(resp.status()) {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                log::warn!(
+                    "{} rejected verbose_json ({}), retrying as text: {}",
+                    name,
+                    status,
+                    body
+                );
+                return send_transcription(url, name, key, &audio, request, false).await;
+            }
+            read_transcription(name, resp, request, verbose).await
+        })
+    }
+}
+
+/// Any OpenAI-compatible chat server, addressed by base URL
+/// (e.g. `http://localhost:8080/v1` for a llama.cpp server).
+pub struct OpenAiChat {
+    base_url: String,
+    api_key: String,
+}
+
+impl OpenAiChat {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self { base_url, api_key }
+    }
+}
+
+impl RefineProvider for OpenAiChat {
+    fn name(&self) -> &'static str {
+        "OpenAI-compatible"
+    }
+
+    fn complete<'a>(&'a self, request: &'a ChatRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(chat(&self.base_url, self.name(), &self.api_key, request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbose_only_for_timings_or_detection() {
+        let mut request = SttRequest {
+            model: DEFAULT_STT_MODEL.to_string(),
+            language: "en".to_string(),
+            vocabulary: Vec::new(),
+            translate: false,
+            timings: false,
+        };
+        assert!(!wants_verbose(&request));
+
+        request.timings = true;
+        assert!(wants_verbose(&request));
+        request.model = "gpt-4o-transcribe".to_string();
+        assert!(!wants_verbose(&request));
+
+        request.model = DEFAULT_STT_MODEL.to_string();
+        request.timings = false;
+        request.language = stt::AUTO_LANGUAGE.to_string();
+        assert!(wants_verbose(&request));
+        request.translate = true;
+        assert!(!wants_verbose(&request));
+    }
+
+    #[test]
+    fn test_text_fallback_only_for_refused_options() {
+        assert!(rejects_verbose(StatusCode::BAD_REQUEST));
+        assert!(rejects_verbose(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        assert!(rejects_verbose(StatusCode::UNPROCESSABLE_ENTITY));
+        // A bad key, an oversized upload, rate limits and outages keep their error
+        assert!(!rejects_verbose(StatusCode::UNAUTHORIZED));
+        assert!(!rejects_verbose(StatusCode::FORBIDDEN));
+        assert!(!rejects_verbose(StatusCode::PAYLOAD_TOO_LARGE));
+        assert!(!rejects_verbose(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!rejects_verbose(StatusCode::BAD_GATEWAY));
+    }
+
+    #[test]
+    fn test_translation_only_with_whisper_models() {
+        let stt = OpenAiStt::new(DEFAULT_BASE_URL.to_string(), String::new());
+        assert!(stt.supports_translation("whisper-1"));
+        assert!(!stt.supports_translation("gpt-4o-transcribe"));
+        assert!(!stt.supports_translation("gpt-4o-mini-transcribe"));
+    }
+
+    #[test]
+    fn test_verbose_json_segments_and_words() {
+        let body: VerboseTranscription = serde_json::from_str(
+            r#"{
+                "text": " Hello there. Bye.",
+                "language": "english",
+                "segments": [
+                    {"start": 0.0, "end": 1.5, "text": " Hello there.", "avg_logprob": 0.0},
+                    {"start": 1.5, "end": 2.25, "text": " Bye.", "avg_logprob": -0.5}
+                ],
+                "words": [
+                    {"word": "Hello", "start": 0.1, "end": 0.5},
+                    {"word": "there", "start": 0.6, "end": 1.0},
+                    {"word": "Bye", "start": 1.6, "end": 2.0}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let segments = body.into_segments();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!((segments[1].start_ms, segments[1].end_ms), (1500, 2250));
+        assert_eq!(segments[0].text, "Hello there.");
+        assert_eq!(segments[0].confidence, Some(1.0));
+        assert_eq!(segments[0].words.len(), 2);
+        assert_eq!(segments[1].words[0].word, "Bye");
+    }
+
+    #[test]
+    fn test_verbose_json_without_timings() {
+        // Translations and some compatible servers return no words or segments
+        let body: VerboseTranscription =
+            serde_json::from_str(r#"{"text": "Hi", "language": "en"}"#).unwrap();
+        assert!(body.into_segments().is_empty());
+    }
+}
@@ -0,0 +1,397 @@
+//! Speech-to-text backends, selected by the `stt_provider` setting.
+
+use futures_util::future::BoxFuture;
+
+use crate::api::deepgram::{self, DeepgramStt};
+use crate::api::groq::Groq;
+use crate::api::openai::{self, OpenAiStt};
+use crate::audio::encoder::{EncodedAudio, UploadFormat};
+use crate::errors::{Result, VoiceFlowError};
+use crate::keychain;
+use crate::storage::database::Database;
+use crate::storage::models::{Segment, WordTiming};
+
+const DEFAULT_GROQ_MODEL: &str = "whisper-large-v3";
+/// `language` value that asks the provider to detect the spoken language.
+pub const AUTO_LANGUAGE: &str = "auto";
+/// Whisper reads at most 224 tokens of prompt and silently drops the rest from the front.
+const WHISPER_PROMPT_TOKENS: usize = 224;
+
+/// Per-request transcription parameters.
+#[derive(Debug, Clone)]
+pub struct SttRequest {
+    pub model: String,
+    pub language: String,
+    /// User vocabulary (names, jargon) the recognizer should be biased towards.
+    pub vocabulary: Vec<String>,
+    /// Translate the speech into English instead of transcribing it (Whisper's
+    /// translate task). Only set when the provider `supports_translation`.
+    pub translate: bool,
+    /// Ask for segment and word timings. Files and subtitles need them; dictation
+    /// only needs the text, which is quicker to produce.
+    pub timings: bool,
+}
+
+/// What a provider returns for one chunk of audio.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    /// Language the provider detected, as an ISO 639-1 code. Only filled in when
+    /// the request asked for detection.
+    pub language: Option<String>,
+    /// Timed segments, relative to the start of the audio sent. Empty when the
+    /// provider reports no timing.
+    pub segments: Vec<Segment>,
+}
+
+impl Transcript {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Seconds, as providers report them, to whole milliseconds.
+pub fn secs_to_ms(secs: f64) -> u64 {
+    (secs.max(0.0) * 1000.0).round() as u64
+}
+
+/// Put each word into the last segment starting at or before it. Whisper reports
+/// words as one flat list next to the segments; words before the first segment
+/// go into it.
+pub fn attach_words(segments: &mut [Segment], words: Vec<WordTiming>) {
+    if segments.is_empty() {
+        return;
+    }
+    for word in words {
+        let index = segments
+            .iter()
+            .rposition(|s| s.start_ms <= word.start_ms)
+            .unwrap_or(0);
+        segments[index].words.push(word);
+    }
+}
+
+impl SttRequest {
+    /// Whether the provider should detect the language instead of being told it.
+    pub fn detect_language(&self) -> bool {
+        self.language == AUTO_LANGUAGE
+    }
+
+    /// Vocabulary as a Whisper `prompt`, cut to fit its token limit. Terms are kept in
+    /// order, so the oldest entries win when the list is too long.
+    pub fn whisper_prompt(&self) -> Option<String> {
+        let mut prompt = String::new();
+        let mut tokens = 0;
+        for (i, term) in self.vocabulary.iter().enumerate() {
+            // +1 for the separating comma
+            let cost = estimate_tokens(term) + 1;
+            if tokens + cost > WHISPER_PROMPT_TOKENS {
+                log::warn!(
+                    "Vocabulary exceeds the Whisper prompt limit; {} of {} terms sent",
+                    i,
+                    self.vocabulary.len()
+                );
+                break;
+            }
+            if !prompt.is_empty() {
+                prompt.push_str(", ");
+            }
+            prompt.push_str(term);
+            tokens += cost;
+        }
+        (!prompt.is_empty()).then_some(prompt)
+    }
+}
+
+/// Whisper language names, as reported by `verbose_json`, and their ISO 639-1 codes.
+const WHISPER_LANGUAGES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("portuguese", "pt"),
+    ("spanish", "es"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("italian", "it"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("chinese", "zh"),
+    ("dutch", "nl"),
+    ("russian", "ru"),
+    ("polish", "pl"),
+    ("ukrainian", "uk"),
+    ("turkish", "tr"),
+    ("arabic", "ar"),
+    ("hindi", "hi"),
+    ("swedish", "sv"),
+    ("norwegian", "no"),
+    ("danish", "da"),
+    ("finnish", "fi"),
+    ("czech", "cs"),
+    ("greek", "el"),
+    ("hebrew", "he"),
+    ("romanian", "ro"),
+    ("hungarian", "hu"),
+    ("catalan", "ca"),
+    ("indonesian", "id"),
+    ("vietnamese", "vi"),
+    ("thai", "th"),
+];
+
+/// Normalize a detected language to an ISO 639-1 code. OpenAI reports Whisper's
+/// lowercase English names ("portuguese"), other servers and Deepgram report codes
+/// (sometimes with a region, "pt-BR"). Unknown names are kept, lowercased.
+pub fn language_code(detected: &str) -> String {
+    let lower = detected.trim().to_lowercase();
+    if let Some((_, code)) = WHISPER_LANGUAGES.iter().find(|(name, _)| *name == lower) {
+        return code.to_string();
+    }
+    match lower.split_once(['-', '_']) {
+        Some((code, _)) if code.len() == 2 => code.to_string(),
+        _ => lower,
+    }
+}
+
+/// Pessimistic token count without the real tokenizer: ASCII averages about four
+/// characters per token, but short words and symbols split more, so three is assumed.
+/// Other scripts can take a token per character or more.
+fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(char::is_ascii).count();
+    let other = text.chars().count() - ascii;
+    ascii.div_ceil(3) + other
+}
+
+/// A backend that turns encoded audio into text.
+pub trait SttProvider: Send + Sync {
+    /// Human-readable name used in logs and error messages.
+    fn name(&self) -> &'static str;
+
+    /// Format this backend needs regardless of the `upload_format` setting.
+    fn preferred_format(&self) -> Option<UploadFormat> {
+        None
+    }
+
+    /// Whether `model` can translate speech into English on its own.
+    fn supports_translation(&self, _model: &str) -> bool {
+        false
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio: EncodedAudio,
+        request: &'a SttRequest,
+    ) -> BoxFuture<'a, Result<Transcript>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SttProviderKind {
+    #[default]
+    Groq,
+    OpenAi,
+    Deepgram,
+    /// whisper.cpp on this machine; only available with the `local-whisper` feature.
+    Local,
+}
+
+impl SttProviderKind {
+    /// Parse the `stt_provider` setting; unknown values fall back to Groq.
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("openai") => Self::OpenAi,
+            Some("deepgram") => Self::Deepgram,
+            Some("local") => Self::Local,
+            _ => Self::Groq,
+        }
+    }
+
+    /// Identifier used in settings and for the provider's keychain entry.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Groq => "groq",
+            Self::OpenAi => "openai",
+            Self::Deepgram => "deepgram",
+            Self::Local => "local",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Groq => "Groq",
+            Self::OpenAi => "OpenAI",
+            Self::Deepgram => "Deepgram",
+            Self::Local => "Local Whisper",
+        }
+    }
+
+    /// Setting holding the model for this provider, and its default.
+    /// For the local backend the "model" is the path to a GGML file.
+    fn model_setting(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Groq => ("stt_model", DEFAULT_GROQ_MODEL),
+            Self::OpenAi => ("openai_stt_model", openai::DEFAULT_STT_MODEL),
+            Self::Deepgram => ("deepgram_model", deepgram::DEFAULT_MODEL),
+            Self::Local => ("whisper_model_path", ""),
+        }
+    }
+
+    /// Self-hosted OpenAI-compatible servers usually run without authentication.
+    fn requires_key(self) -> bool {
+        matches!(self, Self::Groq | Self::Deepgram)
+    }
+}
+
+/// Build the configured STT provider and the model it should use.
+/// The API key comes from the keychain entry of the selected provider, or its
+/// environment variable.
+pub fn from_settings(db: &Database) -> Result<(Box<dyn SttProvider>, String)> {
+    let setting = |key: &str| db.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+
+    let kind = SttProviderKind::from_setting(setting("stt_provider").as_deref());
+    let api_key = match kind {
+        SttProviderKind::Local => String::new(),
+        _ => keychain::resolve_provider_key(kind.id()).unwrap_or_default(),
+    };
+    if api_key.is_empty() && kind.requires_key() {
+        return Err(VoiceFlowError::Pipeline(format!(
+            "No {} API key configured. Set it in Settings.",
+            kind.label()
+        )));
+    }
+
+    let (model_key, default_model) = kind.model_setting();
+    let model = setting(model_key).unwrap_or_else(|| default_model.to_string());
+
+    let provider: Box<dyn SttProvider> = match kind {
+        SttProviderKind::Groq => Box::new(Groq::new(api_key)),
+        SttProviderKind::OpenAi => {
+            let base_url =
+                setting("openai_base_url").unwrap_or_else(|| openai::DEFAULT_BASE_URL.to_string());
+            Box::new(OpenAiStt::new(base_url, api_key))
+        }
+        SttProviderKind::Deepgram => Box::new(DeepgramStt::new(api_key)),
+        SttProviderKind::Local => local_provider(&model)?,
+    };
+    Ok((provider, model))
+}
+
+#[cfg(feature = "local-whisper")]
+fn local_provider(model_path: &str) -> Result<Box<dyn SttProvider>> {
+    if model_path.is_empty() {
+        return Err(VoiceFlowError::Pipeline(
+            "No local Whisper model configured. Set the GGML model path in Settings.".into(),
+        ));
+    }
+    if !std::path::Path::new(model_path).is_file() {
+        return Err(VoiceFlowError::Pipeline(format!(
+            "Whisper model not found: {}",
+            model_path
+        )));
+    }
+    Ok(Box::new(crate::api::local_whisper::LocalWhisper::new(
+        model_path.to_string(),
+    )))
+}
+
+#[cfg(not(feature = "local-whisper"))]
+fn local_provider(_model_path: &str) -> Result<Box<dyn SttProvider>> {
+    Err(VoiceFlowError::Pipeline(
+        "This build has no local Whisper support (cargo feature `local-whisper`).".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(vocabulary: &[&str]) -> SttRequest {
+        SttRequest {
+            model: DEFAULT_GROQ_MODEL.to_string(),
+            language: "en".to_string(),
+            vocabulary: vocabulary.iter().map(|t| t.to_string()).collect(),
+            translate: false,
+            timings: false,
+        }
+    }
+
+    #[test]
+    fn test_language_code_normalizes_names_and_regions() {
+        assert_eq!(language_code("portuguese"), "pt");
+        assert_eq!(language_code("English"), "en");
+        assert_eq!(language_code("es"), "es");
+        assert_eq!(language_code("pt-BR"), "pt");
+        assert_eq!(language_code("zh_TW"), "zh");
+        assert_eq!(language_code("Klingon"), "klingon");
+    }
+
+    fn segment(start_ms: u64, end_ms: u64) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: String::new(),
+            confidence: None,
+            words: Vec::new(),
+        }
+    }
+
+    fn word(start_ms: u64, word: &str) -> WordTiming {
+        WordTiming {
+            start_ms,
+            end_ms: start_ms + 200,
+            word: word.to_string(),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_attach_words_to_segments() {
+        let mut segments = vec![segment(100, 1000), segment(1000, 2500)];
+        attach_words(
+            &mut segments,
+            vec![
+                word(0, "so"),
+                word(400, "hello"),
+                word(1000, "there"),
+                word(2600, "end"),
+            ],
+        );
+
+        let words = |s: &Segment| s.words.iter().map(|w| w.word.clone()).collect::<Vec<_>>();
+        assert_eq!(words(&segments[0]), ["so", "hello"]);
+        assert_eq!(words(&segments[1]), ["there", "end"]);
+    }
+
+    #[test]
+    fn test_secs_to_ms() {
+        assert_eq!(secs_to_ms(1.2345), 1235);
+        assert_eq!(secs_to_ms(-0.01), 0);
+    }
+
+    #[test]
+    fn test_whisper_prompt_joins_terms() {
+        assert_eq!(request(&[]).whisper_prompt(), None);
+        assert_eq!(
+            request(&["Kubernetes", "pgbouncer", "Tauri"]).whisper_prompt(),
+            Some("Kubernetes, pgbouncer, Tauri".to_string())
+        );
+    }
+
+    #[test]
+    fn test_whisper_prompt_respects_token_limit() {
+        let terms: Vec<String> = (0..500).map(|i| format!("term{}", i)).collect();
+        let terms: Vec<&str> = terms.iter().map(String::as_str).collect();
+        let prompt = request(&terms).whisper_prompt().unwrap();
+
+        assert!(prompt.starts_with("term0, term1, "));
+        assert!(!prompt.contains("term499"));
+        let estimated: usize = prompt.split(", ").map(|t| estimate_tokens(t) + 1).sum();
+        assert!(estimated <= WHISPER_PROMPT_TOKENS);
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_non_ascii_per_char() {
+        assert_eq!(estimate_tokens("abcdef"), 2);
+        assert_eq!(estimate_tokens("東京"), 2);
+        assert_eq!(estimate_tokens("ação"), 3);
+    }
+}
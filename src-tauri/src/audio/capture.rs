@@ -1,5 +1,6 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::SampleFormat;
+use serde::Serialize;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 
@@ -8,6 +9,93 @@ use crate::errors::{Result, VoiceFlowError};
 /// Thread-safe audio sample buffer shared between the recording thread and main thread.
 type SampleBuffer = Arc<Mutex<Vec<f32>>>;
 
+/// An audio input device as reported by the host, for the device picker in Settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDevice {
+    pub name: String,
+    pub is_default: bool,
+    pub configs: Vec<InputConfig>,
+}
+
+/// One supported stream configuration range of an input device.
+#[derive(Debug, Clone, Serialize)]
+pub struct InputConfig {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+/// Enumerate the input devices of the default host.
+/// Devices whose name or configs cannot be queried are skipped.
+pub fn list_input_devices() -> Result<Vec<InputDevice>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| VoiceFlowError::Audio(e.to_string()))?;
+
+    let mut result = Vec::new();
+    for device in devices {
+        let Ok(name) = device.name() else {
+            continue;
+        };
+        let configs = match device.supported_input_configs() {
+            Ok(configs) => configs
+                .map(|c| InputConfig {
+                    channels: c.channels(),
+                    min_sample_rate: c.min_sample_rate().0,
+                    max_sample_rate: c.max_sample_rate().0,
+                    sample_format: c.sample_format().to_string(),
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("Skipping input device '{}': {}", name, e);
+                continue;
+            }
+        };
+        result.push(InputDevice {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+            configs,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Resolve the input device to record from.
+/// Uses the named device when given, falling back to the host default if it has gone away;
+/// the flag tells whether that fallback happened.
+fn resolve_input_device(device_name: Option<&str>) -> Result<(cpal::Device, bool)> {
+    let host = cpal::default_host();
+    let mut fell_back = false;
+
+    if let Some(wanted) = device_name.filter(|n| !n.is_empty()) {
+        let found = host
+            .input_devices()
+            .map_err(|e| VoiceFlowError::Audio(e.to_string()))?
+            .find(|d| d.name().map(|n| n == wanted).unwrap_or(false));
+
+        match found {
+            Some(device) => return Ok((device, false)),
+            None => {
+                let err = VoiceFlowError::Audio(format!(
+                    "Input device '{}' not found, falling back to default",
+                    wanted
+                ));
+                log::warn!("{}", err);
+                fell_back = true;
+            }
+        }
+    }
+
+    host.default_input_device()
+        .map(|device| (device, fell_back))
+        .ok_or_else(|| VoiceFlowError::Audio("No input device found".into()))
+}
+
 /// Audio recording state. The cpal Stream is managed on a dedicated thread
 /// (because cpal Stream is not Send+Sync), controlled via channels.
 /// All fields of AudioState itself are Send+Sync.
@@ -48,9 +136,11 @@ impl AudioState {
         self.recording.load(std::sync::atomic::Ordering::Relaxed)
     }
 
-    pub fn start_recording(&mut self) -> Result<()> {
+    /// Start recording from `device_name`, or the default input device when `None`.
+    /// Returns true when `device_name` was not found and the default device is used instead.
+    pub fn start_recording(&mut self, device_name: Option<&str>) -> Result<bool> {
         if self.is_recording() {
-            return Ok(());
+            return Ok(false);
         }
 
         // Clear previous samples
@@ -58,10 +148,8 @@ impl AudioState {
             samples.clear();
         }
 
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or_else(|| VoiceFlowError::Audio("No input device found".into()))?;
+        let (device, fell_back) = resolve_input_device(device_name)?;
+        let device_label = device.name().unwrap_or_else(|_| "unknown".into());
 
         let config = device
             .default_input_config()
@@ -166,8 +254,12 @@ impl AudioState {
         });
 
         self.join_handle = Some(handle);
-        log::info!("Recording started at {} Hz", self.sample_rate);
-        Ok(())
+        log::info!(
+            "Recording started at {} Hz on '{}'",
+            self.sample_rate,
+            device_label
+        );
+        Ok(fell_back)
     }
 
     pub fn stop_recording(&mut self) -> Result<(Vec<f32>, u32)> {
@@ -190,4 +282,11 @@ impl AudioState {
         log::info!("Recording stopped, {} samples captured", samples.len());
         Ok((samples, self.sample_rate))
     }
+
+    /// Stop recording and discard everything captured so far.
+    pub fn cancel_recording(&mut self) -> Result<()> {
+        let (samples, _) = self.stop_recording()?;
+        log::info!("Recording cancelled, {} samples discarded", samples.len());
+        Ok(())
+    }
 }
@@ -0,0 +1,173 @@
+//! Splitting of long recordings into pieces the STT endpoint accepts.
+
+use crate::audio::vad;
+use std::ops::Range;
+
+/// Longest chunk sent in one request. Well under Groq's 25 MB upload limit even as
+/// WAV (~13 min), and short enough that an hour of audio fans out into parallel requests.
+const MAX_CHUNK_SECS: u32 = 300;
+/// How far back from the chunk limit to look for a pause to cut at.
+const SEARCH_WINDOW_SECS: u32 = 30;
+/// Quiet stretch long enough to count as a pause between words.
+const PAUSE_MS: u32 = 300;
+/// Audio repeated at the start of the next chunk when no pause was found, so a word
+/// cut in half is heard whole by one of the two requests.
+const OVERLAP_SECS: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Sample range within the recording.
+    pub range: Range<usize>,
+    /// Starts before the end of the previous chunk (hard cut), so the transcripts
+    /// share a few words that must be de-duplicated when stitching.
+    pub overlaps_previous: bool,
+}
+
+/// Split a recording into chunks, cutting at pauses where possible and falling back
+/// to fixed windows with overlap during continuous speech.
+pub fn split(samples: &[f32], sample_rate: u32) -> Vec<Chunk> {
+    split_with_limit(samples, sample_rate, MAX_CHUNK_SECS)
+}
+
+fn split_with_limit(samples: &[f32], sample_rate: u32, max_secs: u32) -> Vec<Chunk> {
+    let secs = |s: u32| s as usize * sample_rate as usize;
+    let max_len = secs(max_secs);
+    if samples.len() <= max_len {
+        return vec![Chunk {
+            range: 0..samples.len(),
+            overlaps_previous: false,
+        }];
+    }
+
+    let frame_len = vad::frame_len(sample_rate);
+    let frames = vad::analyze(samples, frame_len);
+    let threshold = vad::speech_threshold(&frames);
+    let pause_frames = (PAUSE_MS / vad::FRAME_MS) as usize;
+    let search_len = secs(SEARCH_WINDOW_SECS).min(max_len / 2);
+    let overlap_len = secs(OVERLAP_SECS).min(max_len / 4);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut overlaps_previous = false;
+    while samples.len() - start > max_len {
+        let limit = start + max_len;
+        let window = limit - search_len..limit;
+        let (end, next_start, next_overlaps) =
+            match find_pause(&frames, frame_len, window, threshold, pause_frames) {
+                Some(cut) => (cut, cut, false),
+                None => (limit, limit - overlap_len, true),
+            };
+        chunks.push(Chunk {
+            range: start..end,
+            overlaps_previous,
+        });
+        start = next_start;
+        overlaps_previous = next_overlaps;
+    }
+    chunks.push(Chunk {
+        range: start..samples.len(),
+        overlaps_previous,
+    });
+    chunks
+}
+
+/// Middle of the latest pause inside `window` (sample indices). Later pauses are
+/// preferred so chunks stay close to the maximum length.
+fn find_pause(
+    frames: &[vad::Frame],
+    frame_len: usize,
+    window: Range<usize>,
+    threshold: f32,
+    pause_frames: usize,
+) -> Option<usize> {
+    let first = window.start.div_ceil(frame_len);
+    let last = (window.end / frame_len).min(frames.len());
+    let mut run = 0;
+    for i in (first..last).rev() {
+        if frames[i].rms < threshold {
+            run += 1;
+            if run >= pause_frames {
+                return Some((i + run / 2) * frame_len);
+            }
+        } else {
+            run = 0;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn tone(secs: f32) -> Vec<f32> {
+        (0..(RATE as f32 * secs) as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * 200.0 * i as f32 / RATE as f32).sin() * 0.3)
+            .collect()
+    }
+
+    fn assert_covers(chunks: &[Chunk], len: usize) {
+        assert_eq!(chunks.first().unwrap().range.start, 0);
+        assert_eq!(chunks.last().unwrap().range.end, len);
+        for pair in chunks.windows(2) {
+            if pair[1].overlaps_previous {
+                assert!(pair[1].range.start < pair[0].range.end);
+            } else {
+                assert_eq!(pair[1].range.start, pair[0].range.end);
+            }
+        }
+    }
+
+    #[test]
+    fn test_short_recording_is_single_chunk() {
+        let samples = tone(5.0);
+        let chunks = split_with_limit(&samples, RATE, 10);
+        assert_eq!(
+            chunks,
+            vec![Chunk {
+                range: 0..samples.len(),
+                overlaps_previous: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cuts_at_pauses() {
+        // 4 s phrases separated by 1 s pauses, 25 s total
+        let mut samples = Vec::new();
+        for _ in 0..5 {
+            samples.extend(tone(4.0));
+            samples.extend(vec![0.0; RATE as usize]);
+        }
+        let chunks = split_with_limit(&samples, RATE, 10);
+
+        assert!(chunks.len() >= 3);
+        assert_covers(&chunks, samples.len());
+        for chunk in &chunks {
+            assert!(!chunk.overlaps_previous);
+            assert!(chunk.range.len() <= 10 * RATE as usize);
+            // Every cut lands inside a silent gap
+            let cut = chunk.range.end;
+            if cut < samples.len() {
+                assert_eq!(samples[cut], 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlaps_during_continuous_speech() {
+        let samples = tone(25.0);
+        let chunks = split_with_limit(&samples, RATE, 10);
+
+        assert_eq!(chunks.len(), 3);
+        assert_covers(&chunks, samples.len());
+        assert!(!chunks[0].overlaps_previous);
+        assert!(chunks[1..].iter().all(|c| c.overlaps_previous));
+        assert_eq!(
+            chunks[0].range.end - chunks[1].range.start,
+            2 * RATE as usize
+        );
+    }
+}
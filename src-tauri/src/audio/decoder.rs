@@ -0,0 +1,158 @@
+//! Decoding of audio and video files (WAV, FLAC, MP3, Ogg Vorbis/Opus, MP4/M4A, MKV)
+//! into mono samples for transcription.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek};
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::ogg_opus;
+use crate::errors::{Result, VoiceFlowError};
+
+/// Decode the first audio track of a file, averaging its channels down to mono.
+/// Returns the samples at the file's own rate; `encoder` resamples them for upload.
+pub fn decode_file(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let mut file = File::open(path)?;
+    // Phone voice notes are mostly Ogg/Opus, which symphonia has no codec for
+    let mut magic = Vec::new();
+    file.by_ref().take(36).read_to_end(&mut magic)?;
+    if ogg_opus::is_ogg_opus(&magic) {
+        log::info!("Decoding {} as Ogg/Opus", path.display());
+        return ogg_opus::decode(&std::fs::read(path)?);
+    }
+    file.rewind()?;
+
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| {
+            VoiceFlowError::Audio(format!("Unsupported file {}: {}", path.display(), e))
+        })?;
+    let mut reader = probed.format;
+
+    let track = reader
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| VoiceFlowError::Audio(format!("No audio track in {}", path.display())))?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate.ok_or_else(|| {
+        VoiceFlowError::Audio(format!("Unknown sample rate in {}", path.display()))
+    })?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(decode_error)?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(decode_error(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only loses its own few milliseconds
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(decode_error(e)),
+        };
+        let spec = *decoded.spec();
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        downmix(buffer.samples(), spec.channels.count(), &mut samples);
+    }
+
+    log::info!(
+        "Decoded {} ({:.1}s at {} Hz)",
+        path.display(),
+        samples.len() as f64 / sample_rate as f64,
+        sample_rate
+    );
+    Ok((samples, sample_rate))
+}
+
+/// Append interleaved frames to `out` as mono, averaging the channels.
+fn downmix(interleaved: &[f32], channels: usize, out: &mut Vec<f32>) {
+    if channels <= 1 {
+        out.extend_from_slice(interleaved);
+        return;
+    }
+    out.extend(
+        interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+    );
+}
+
+fn decode_error(e: SymphoniaError) -> VoiceFlowError {
+    VoiceFlowError::Audio(format!("Decoding failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::flac;
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        let mut out = Vec::new();
+        downmix(&[0.5, -0.5, 1.0, 0.0], 2, &mut out);
+        assert_eq!(out, [0.0, 0.5]);
+
+        downmix(&[0.25], 1, &mut out);
+        assert_eq!(out, [0.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_decodes_flac_file() {
+        let pcm: Vec<i16> = (0..16000)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        let path =
+            std::env::temp_dir().join(format!("voiceflow-decoder-{}.flac", std::process::id()));
+        std::fs::write(&path, flac::encode(&pcm, 16000)).unwrap();
+
+        let (samples, rate) = decode_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rate, 16000);
+        assert_eq!(samples.len(), pcm.len());
+        for (decoded, original) in samples.iter().zip(&pcm) {
+            assert!((decoded - *original as f32 / 32768.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_audio_file() {
+        let path =
+            std::env::temp_dir().join(format!("voiceflow-decoder-{}.txt", std::process::id()));
+        std::fs::write(&path, "not audio").unwrap();
+        let result = decode_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(VoiceFlowError::Audio(_))));
+    }
+}
@@ -1,15 +1,98 @@
+use crate::audio::{flac, ogg_opus};
 use crate::errors::{Result, VoiceFlowError};
 use std::io::Cursor;
+use std::time::Duration;
 
 const TARGET_SAMPLE_RATE: u32 = 16000;
 
-/// Resample audio to 16kHz if needed, then encode as WAV (16-bit mono).
-pub fn encode_wav(samples: &[f32], source_rate: u32) -> Result<Vec<u8>> {
-    let resampled = if source_rate != TARGET_SAMPLE_RATE {
+/// Container/codec used for the audio sent to the STT API (`upload_format` setting).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UploadFormat {
+    /// Uncompressed 16-bit PCM; largest upload, no encoding cost.
+    #[default]
+    Wav,
+    /// Lossless, roughly half the size of WAV for speech.
+    Flac,
+    /// Lossy Opus in an Ogg container; ~10x smaller than WAV, best on slow uplinks.
+    Opus,
+}
+
+impl UploadFormat {
+    /// Parse the `upload_format` setting; unknown values fall back to WAV.
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("flac") => Self::Flac,
+            Some("opus") => Self::Opus,
+            _ => Self::Wav,
+        }
+    }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Wav => "audio.wav",
+            Self::Flac => "audio.flac",
+            Self::Opus => "audio.ogg",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
+            Self::Opus => "audio/ogg",
+        }
+    }
+}
+
+/// Encoded audio ready for upload, with the file name and MIME type the API should see.
+#[derive(Debug, Clone)]
+pub struct EncodedAudio {
+    pub data: Vec<u8>,
+    pub file_name: &'static str,
+    pub mime_type: &'static str,
+    /// Length of the audio, which bounds how long the upload may take.
+    pub duration: Duration,
+}
+
+/// Resample audio to 16kHz if needed, then encode it in the requested upload format.
+pub fn encode(samples: &[f32], source_rate: u32, format: UploadFormat) -> Result<EncodedAudio> {
+    let data = match format {
+        UploadFormat::Wav => encode_wav(samples, source_rate)?,
+        UploadFormat::Flac => {
+            let pcm = to_i16(&to_target_rate(samples, source_rate));
+            flac::encode(&pcm, TARGET_SAMPLE_RATE)
+        }
+        UploadFormat::Opus => {
+            ogg_opus::encode(&to_target_rate(samples, source_rate), TARGET_SAMPLE_RATE)?
+        }
+    };
+
+    Ok(EncodedAudio {
+        data,
+        file_name: format.file_name(),
+        mime_type: format.mime_type(),
+        duration: Duration::from_secs_f64(samples.len() as f64 / source_rate.max(1) as f64),
+    })
+}
+
+fn to_target_rate(samples: &[f32], source_rate: u32) -> Vec<f32> {
+    if source_rate != TARGET_SAMPLE_RATE {
         resample(samples, source_rate, TARGET_SAMPLE_RATE)
     } else {
         samples.to_vec()
-    };
+    }
+}
+
+fn to_i16(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+        .collect()
+}
+
+/// Resample audio to 16kHz if needed, then encode as WAV (16-bit mono).
+pub fn encode_wav(samples: &[f32], source_rate: u32) -> Result<Vec<u8>> {
+    let resampled = to_target_rate(samples, source_rate);
 
     let mut cursor = Cursor::new(Vec::new());
     let spec = hound::WavSpec {
@@ -22,9 +105,7 @@ pub fn encode_wav(samples: &[f32], source_rate: u32) -> Result<Vec<u8>> {
     let mut writer =
         hound::WavWriter::new(&mut cursor, spec).map_err(|e| VoiceFlowError::Audio(e.to_string()))?;
 
-    for &sample in &resampled {
-        let clamped = sample.clamp(-1.0, 1.0);
-        let int_sample = (clamped * 32767.0) as i16;
+    for int_sample in to_i16(&resampled) {
         writer
             .write_sample(int_sample)
             .map_err(|e| VoiceFlowError::Audio(e.to_string()))?;
@@ -37,25 +118,70 @@ pub fn encode_wav(samples: &[f32], source_rate: u32) -> Result<Vec<u8>> {
     Ok(cursor.into_inner())
 }
 
-/// Simple linear interpolation resampling.
+/// Number of sinc zero crossings on each side of the filter centre.
+const SINC_ZERO_CROSSINGS: f64 = 24.0;
+/// Low-pass cutoff as a fraction of the lower of the two Nyquist frequencies.
+const SINC_ROLLOFF: f64 = 0.9;
+/// Kaiser window shape parameter (~80 dB stopband attenuation).
+const KAISER_BETA: f64 = 7.857;
+/// Upper bound on the polyphase filter count; finer phases snap to the nearest filter.
+const MAX_PHASES: usize = 1024;
+
+/// Band-limited resampling with a Kaiser-windowed sinc low-pass (polyphase implementation).
+/// The low-pass removes content above the target Nyquist before decimation, so
+/// high-frequency noise does not fold back into the speech band.
 fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-    if samples.is_empty() {
+    if samples.is_empty() || from_rate == 0 || to_rate == 0 {
         return Vec::new();
     }
+    if from_rate == to_rate {
+        return samples.to_vec();
+    }
+
+    let g = gcd(from_rate, to_rate);
+    let up = (to_rate / g) as u64;
+    let down = (from_rate / g) as u64;
 
-    let ratio = from_rate as f64 / to_rate as f64;
-    let output_len = (samples.len() as f64 / ratio) as usize;
+    // Cutoff normalized to the input Nyquist frequency (1.0 = from_rate / 2)
+    let cutoff = SINC_ROLLOFF * from_rate.min(to_rate) as f64 / from_rate as f64;
+    let half = (SINC_ZERO_CROSSINGS / cutoff).ceil() as usize;
+    let taps = 2 * half;
+    let phases = (up as usize).min(MAX_PHASES);
+    let bank = build_filter_bank(phases, half, cutoff);
+
+    let output_len = (samples.len() as u64 * up / down) as usize;
     let mut output = Vec::with_capacity(output_len);
 
-    for i in 0..output_len {
-        let src_idx = i as f64 * ratio;
-        let idx = src_idx as usize;
-        let frac = (src_idx - idx as f64) as f32;
+    for n in 0..output_len as u64 {
+        let pos = n * down;
+        let mut base = (pos / up) as usize;
+        let rem = (pos % up) as usize;
+        let mut phase = if phases as u64 == up {
+            rem
+        } else {
+            (rem * phases + up as usize / 2) / up as usize
+        };
+        if phase == phases {
+            phase = 0;
+            base += 1;
+        }
 
-        let sample = if idx + 1 < samples.len() {
-            samples[idx] * (1.0 - frac) + samples[idx + 1] * frac
+        let coefs = &bank[phase * taps..(phase + 1) * taps];
+        let start = base as isize - (half as isize - 1);
+
+        let sample = if start >= 0 && start as usize + taps <= samples.len() {
+            let window = &samples[start as usize..start as usize + taps];
+            window.iter().zip(coefs).map(|(x, c)| x * c).sum()
         } else {
-            samples[idx.min(samples.len() - 1)]
+            // Near the edges: treat samples outside the buffer as silence
+            coefs
+                .iter()
+                .enumerate()
+                .filter_map(|(j, c)| {
+                    let idx = start + j as isize;
+                    (idx >= 0 && (idx as usize) < samples.len()).then(|| samples[idx as usize] * c)
+                })
+                .sum()
         };
         output.push(sample);
     }
@@ -63,6 +189,67 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     output
 }
 
+/// Precompute `phases` low-pass filters of `2 * half` taps, one per fractional offset.
+/// Each filter is normalized to unity DC gain.
+fn build_filter_bank(phases: usize, half: usize, cutoff: f64) -> Vec<f32> {
+    let taps = 2 * half;
+    let window_norm = bessel_i0(KAISER_BETA);
+    let mut bank = Vec::with_capacity(phases * taps);
+
+    for p in 0..phases {
+        let frac = p as f64 / phases as f64;
+        let filter: Vec<f64> = (0..taps)
+            .map(|j| {
+                // Distance (in input samples) between the output position and tap j
+                let d = frac + (half - 1) as f64 - j as f64;
+                let x = d / half as f64;
+                let window = if x.abs() >= 1.0 {
+                    0.0
+                } else {
+                    bessel_i0(KAISER_BETA * (1.0 - x * x).sqrt()) / window_norm
+                };
+                cutoff * sinc(cutoff * d) * window
+            })
+            .collect();
+
+        let sum: f64 = filter.iter().sum();
+        bank.extend(filter.iter().map(|c| (c / sum) as f32));
+    }
+
+    bank
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series).
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..50 {
+        term *= (half_x / k as f64).powi(2);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +265,29 @@ mod tests {
         assert!(wav.len() > 44); // header + data
     }
 
+    #[test]
+    fn test_upload_format_from_setting() {
+        assert_eq!(UploadFormat::from_setting(Some("flac")), UploadFormat::Flac);
+        assert_eq!(UploadFormat::from_setting(Some("opus")), UploadFormat::Opus);
+        assert_eq!(UploadFormat::from_setting(Some("wav")), UploadFormat::Wav);
+        assert_eq!(UploadFormat::from_setting(Some("mp3")), UploadFormat::Wav);
+        assert_eq!(UploadFormat::from_setting(None), UploadFormat::Wav);
+    }
+
+    #[test]
+    fn test_encode_flac_resamples_and_labels() {
+        let samples = tone(440.0, 48000, 0.5);
+        let audio = encode(&samples, 48000, UploadFormat::Flac).unwrap();
+        assert_eq!(&audio.data[0..4], b"fLaC");
+        assert_eq!(audio.file_name, "audio.flac");
+        assert_eq!(audio.mime_type, "audio/flac");
+        // STREAMINFO sample rate (20 bits at byte offset 18) is the 16kHz target
+        let rate = ((audio.data[18] as u32) << 12)
+            | ((audio.data[19] as u32) << 4)
+            | ((audio.data[20] as u32) >> 4);
+        assert_eq!(rate, 16000);
+    }
+
     #[test]
     fn test_resample_halves_rate() {
         let samples: Vec<f32> = (0..1000).map(|i| (i as f32 / 1000.0).sin()).collect();
@@ -86,4 +296,80 @@ mod tests {
         let expected_len = (1000.0 * 16000.0 / 48000.0) as usize;
         assert!((resampled.len() as i64 - expected_len as i64).abs() <= 1);
     }
+
+    fn tone(freq: f64, rate: u32, seconds: f64) -> Vec<f32> {
+        let len = (rate as f64 * seconds) as usize;
+        (0..len)
+            .map(|i| (0.5 * (2.0 * std::f64::consts::PI * freq * i as f64 / rate as f64).sin()) as f32)
+            .collect()
+    }
+
+    /// RMS level in dB relative to the 0.5-amplitude test tone, ignoring filter edge effects.
+    fn level_db(samples: &[f32]) -> f64 {
+        let edge = samples.len() / 10;
+        let body = &samples[edge..samples.len() - edge];
+        let rms = (body.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / body.len() as f64).sqrt();
+        let reference = 0.5 / 2f64.sqrt();
+        20.0 * (rms / reference).max(1e-12).log10()
+    }
+
+    #[test]
+    fn test_resample_preserves_passband() {
+        for &from in &[48000, 44100] {
+            for &freq in &[300.0, 1000.0, 5000.0] {
+                let out = resample(&tone(freq, from, 0.5), from, 16000);
+                let db = level_db(&out);
+                assert!(db.abs() < 0.1, "{} Hz from {} Hz: {:.3} dB", freq, from, db);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resample_attenuates_aliasing() {
+        // Tones above the 8 kHz target Nyquist would fold back into the speech band
+        for &from in &[48000, 44100] {
+            for &freq in &[9000.0, 12000.0, 15000.0, 20000.0] {
+                let out = resample(&tone(freq, from, 0.5), from, 16000);
+                let db = level_db(&out);
+                assert!(db < -60.0, "{} Hz from {} Hz aliased at {:.1} dB", freq, from, db);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resample_upsamples() {
+        let input = tone(1000.0, 8000, 0.5);
+        let out = resample(&input, 8000, 16000);
+        assert_eq!(out.len(), input.len() * 2);
+        assert!(level_db(&out).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_resample_identity_and_empty() {
+        let input = tone(440.0, 16000, 0.1);
+        assert_eq!(resample(&input, 16000, 16000), input);
+        assert!(resample(&[], 48000, 16000).is_empty());
+    }
+
+    /// Throughput check: `cargo test --release -- --ignored bench_resample`
+    #[test]
+    #[ignore]
+    fn bench_resample_five_minutes_realtime() {
+        let seconds = 300.0;
+        for &from in &[48000, 44100] {
+            let input = tone(440.0, from, seconds);
+            let started = std::time::Instant::now();
+            let out = resample(&input, from, 16000);
+            let elapsed = started.elapsed().as_secs_f64();
+            println!(
+                "{} Hz -> 16 kHz, {} s of audio: {:.2} s ({:.0}x real-time)",
+                from,
+                seconds,
+                elapsed,
+                seconds / elapsed
+            );
+            assert_eq!(out.len(), (seconds * 16000.0) as usize);
+            assert!(elapsed < seconds, "resampler is slower than real-time");
+        }
+    }
 }
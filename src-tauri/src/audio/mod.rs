@@ -1,2 +1,7 @@
 pub mod capture;
+pub mod chunker;
+pub mod decoder;
 pub mod encoder;
+pub mod flac;
+pub mod ogg_opus;
+pub mod vad;
@@ -0,0 +1,236 @@
+//! Ogg/Opus encoding of mono speech and decoding of Opus voice notes
+//! (RFC 7845 stream layout).
+
+use crate::errors::{Result, VoiceFlowError};
+use ogg::reading::PacketReader;
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use opus::{Application, Bitrate, Channels, Decoder, Encoder};
+use std::io::Cursor;
+
+/// Opus frame length; 20 ms is the codec's sweet spot for speech.
+const FRAME_MS: u32 = 20;
+/// Plenty for wideband speech recognition while keeping uploads tiny.
+const BITRATE: i32 = 24_000;
+/// Ogg granule positions are always counted at 48 kHz for Opus.
+const GRANULE_RATE: u64 = 48_000;
+const STREAM_SERIAL: u32 = 0x566F_6963; // "Voic"
+const MAX_PACKET_SIZE: usize = 4000;
+/// Longest Opus packet: 120 ms at 48 kHz.
+const MAX_FRAME_SAMPLES: usize = 5760;
+
+/// Encode mono f32 samples as an Ogg/Opus file.
+/// `sample_rate` must be one Opus accepts natively (8, 12, 16, 24 or 48 kHz).
+pub fn encode(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let mut encoder =
+        Encoder::new(sample_rate, Channels::Mono, Application::Voip).map_err(opus_error)?;
+    encoder
+        .set_bitrate(Bitrate::Bits(BITRATE))
+        .map_err(opus_error)?;
+    let lookahead = encoder.get_lookahead().map_err(opus_error)?.max(0) as usize;
+
+    let granule_scale = GRANULE_RATE / sample_rate as u64;
+    let pre_skip = lookahead as u64 * granule_scale;
+    let frame_len = (sample_rate * FRAME_MS / 1000) as usize;
+
+    let mut writer = PacketWriter::new(Vec::new());
+    writer
+        .write_packet(
+            opus_head(pre_skip as u16, sample_rate),
+            STREAM_SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .map_err(io_error)?;
+    writer
+        .write_packet(opus_tags(), STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(io_error)?;
+
+    // Feed `lookahead` extra samples of silence so the tail isn't lost in the encoder delay
+    let mut padded = samples.to_vec();
+    padded.resize(samples.len() + lookahead, 0.0);
+    let frame_count = padded.len().div_ceil(frame_len).max(1);
+    padded.resize(frame_count * frame_len, 0.0);
+
+    let mut packet = vec![0u8; MAX_PACKET_SIZE];
+    for (i, frame) in padded.chunks(frame_len).enumerate() {
+        let len = encoder
+            .encode_float(frame, &mut packet)
+            .map_err(opus_error)?;
+        let last = i + 1 == frame_count;
+        // The final granule position marks the true end, so decoders drop the padding
+        let granule = if last {
+            pre_skip + samples.len() as u64 * granule_scale
+        } else {
+            pre_skip + ((i + 1) * frame_len) as u64 * granule_scale
+        };
+        let end_info = if last {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(Box::from(&packet[..len]), STREAM_SERIAL, end_info, granule)
+            .map_err(io_error)?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// Whether `data` starts like an Ogg stream carrying Opus, which the generic file
+/// decoder cannot play.
+pub fn is_ogg_opus(data: &[u8]) -> bool {
+    // The first page holds only the identification header, right after the page
+    // header and its one-entry segment table
+    data.starts_with(b"OggS") && data.get(28..36) == Some(b"OpusHead".as_slice())
+}
+
+/// Decode an Ogg/Opus file (mono or stereo) to mono f32 samples at 48 kHz, the rate
+/// Opus always decodes at.
+pub fn decode(data: &[u8]) -> Result<(Vec<f32>, u32)> {
+    let mut reader = PacketReader::new(Cursor::new(data));
+    let head = reader
+        .read_packet()
+        .map_err(ogg_error)?
+        .ok_or_else(|| VoiceFlowError::Audio("Empty Ogg stream".into()))?;
+    if head.data.len() < 19 || !head.data.starts_with(b"OpusHead") {
+        return Err(VoiceFlowError::Audio("Not an Opus stream".into()));
+    }
+    let serial = head.stream_serial();
+    let channel_count = head.data[9] as usize;
+    let channels = match channel_count {
+        1 => Channels::Mono,
+        2 => Channels::Stereo,
+        n => {
+            return Err(VoiceFlowError::Audio(format!(
+                "Unsupported Opus channel count: {}",
+                n
+            )))
+        }
+    };
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as usize;
+    let mut decoder = Decoder::new(GRANULE_RATE as u32, channels).map_err(opus_error)?;
+
+    let mut samples = Vec::new();
+    let mut pcm = vec![0f32; MAX_FRAME_SAMPLES * channel_count];
+    let mut end_granule = None;
+    let mut headers_left = 1; // OpusTags
+    while let Some(packet) = reader.read_packet().map_err(ogg_error)? {
+        if packet.stream_serial() != serial {
+            continue;
+        }
+        if headers_left > 0 {
+            headers_left -= 1;
+            continue;
+        }
+        let frames = decoder
+            .decode_float(&packet.data, &mut pcm, false)
+            .map_err(opus_error)?;
+        let decoded = &pcm[..frames * channel_count];
+        samples.extend(
+            decoded
+                .chunks_exact(channel_count)
+                .map(|frame| frame.iter().sum::<f32>() / channel_count as f32),
+        );
+        if packet.last_in_stream() {
+            end_granule = Some(packet.absgp_page() as usize);
+        }
+    }
+
+    // Drop the encoder delay and, when the stream says where it ends, the padding
+    if let Some(end) = end_granule {
+        samples.truncate(end);
+    }
+    samples.drain(..pre_skip.min(samples.len()));
+    Ok((samples, GRANULE_RATE as u32))
+}
+
+/// Identification header ("OpusHead", mapping family 0).
+fn opus_head(pre_skip: u16, input_rate: u32) -> Box<[u8]> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    head.into_boxed_slice()
+}
+
+/// Comment header ("OpusTags") with a vendor string and no user comments.
+fn opus_tags() -> Box<[u8]> {
+    let vendor = b"voice-flow";
+    let mut tags = Vec::with_capacity(8 + 4 + vendor.len() + 4);
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags.into_boxed_slice()
+}
+
+fn opus_error(e: opus::Error) -> VoiceFlowError {
+    VoiceFlowError::Audio(format!("Opus codec: {}", e))
+}
+
+fn io_error(e: std::io::Error) -> VoiceFlowError {
+    VoiceFlowError::Audio(format!("Ogg writer: {}", e))
+}
+
+fn ogg_error(e: ogg::OggReadError) -> VoiceFlowError {
+    VoiceFlowError::Audio(format!("Ogg reader: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::{self, UploadFormat};
+
+    #[test]
+    fn test_decode_restores_length() {
+        let samples: Vec<f32> = (0..16_007).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let encoded = encode(&samples, 16000).unwrap();
+        assert!(is_ogg_opus(&encoded));
+
+        // Pre-skip and end padding are removed; the rate is always 48 kHz
+        let (decoded, rate) = decode(&encoded).unwrap();
+        assert_eq!(rate, 48000);
+        assert_eq!(decoded.len(), samples.len() * 3);
+    }
+
+    #[test]
+    fn test_upload_round_trip() {
+        // Already at 16 kHz, so the upload encoder hands the samples over unchanged
+        let samples: Vec<f32> = (0..16_123).map(|i| (i as f32 * 0.07).sin() * 0.4).collect();
+        let audio = encoder::encode(&samples, 16000, UploadFormat::Opus).unwrap();
+        assert_eq!(audio.file_name, "audio.ogg");
+        assert_eq!(audio.mime_type, "audio/ogg");
+
+        let mut reader = PacketReader::new(Cursor::new(&audio.data));
+        let head = reader.read_packet().unwrap().unwrap();
+        let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as u64;
+        let mut reference = Encoder::new(16000, Channels::Mono, Application::Voip).unwrap();
+        let lookahead = reference.get_lookahead().unwrap() as u64;
+        // Pre-skip covers the encoder delay, counted at 48 kHz
+        assert_eq!(pre_skip, lookahead * 3);
+
+        let mut last = None;
+        while let Some(packet) = reader.read_packet().unwrap() {
+            last = Some(packet);
+        }
+        let last = last.unwrap();
+        assert!(last.last_in_stream());
+        // The final granule marks the real end, before the last frame's padding
+        assert_eq!(last.absgp_page(), pre_skip + samples.len() as u64 * 3);
+
+        let (decoded, rate) = decode(&audio.data).unwrap();
+        assert_eq!(rate, 48000);
+        assert_eq!(decoded.len(), samples.len() * 3);
+    }
+
+    #[test]
+    fn test_is_ogg_opus_rejects_other_files() {
+        assert!(!is_ogg_opus(b"RIFF\0\0\0\0WAVEfmt "));
+        assert!(!is_ogg_opus(b"OggS"));
+        assert!(decode(b"not ogg").is_err());
+    }
+}
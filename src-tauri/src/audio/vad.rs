@@ -0,0 +1,196 @@
+use crate::errors::{Result, VoiceFlowError};
+use std::ops::Range;
+
+/// Analysis frame length.
+pub(crate) const FRAME_MS: u32 = 20;
+/// RMS below this is always treated as silence (about -46 dBFS).
+const MIN_SPEECH_RMS: f32 = 0.005;
+/// A frame is speech when its RMS exceeds the noise floor by this factor (~10 dB).
+const NOISE_FLOOR_FACTOR: f32 = 3.0;
+/// The threshold never exceeds this fraction of the loudest frame, so a recording
+/// without any pause (where the "noise floor" is really speech) is not rejected.
+const PEAK_FRACTION: f32 = 0.3;
+/// Fraction of quietest frames used to estimate the noise floor.
+const NOISE_FLOOR_PERCENTILE: f32 = 0.1;
+/// Zero-crossing rate above which a quieter frame still counts as speech (fricatives like "s", "f").
+const FRICATIVE_ZCR: f32 = 0.25;
+/// Minimum total amount of speech for a recording to be sent at all.
+const MIN_SPEECH_MS: u32 = 120;
+/// Audio kept before the first and after the last speech frame, so word edges aren't clipped.
+const PAD_BEFORE_MS: u32 = 200;
+const PAD_AFTER_MS: u32 = 300;
+
+pub(crate) struct Frame {
+    pub rms: f32,
+    pub zcr: f32,
+}
+
+pub(crate) fn frame_len(sample_rate: u32) -> usize {
+    (sample_rate * FRAME_MS / 1000).max(1) as usize
+}
+
+pub(crate) fn analyze(samples: &[f32], frame_len: usize) -> Vec<Frame> {
+    samples
+        .chunks(frame_len)
+        .map(|chunk| {
+            let energy = chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32;
+            let crossings = chunk
+                .windows(2)
+                .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+                .count();
+            Frame {
+                rms: energy.sqrt(),
+                zcr: crossings as f32 / chunk.len() as f32,
+            }
+        })
+        .collect()
+}
+
+fn noise_floor(frames: &[Frame]) -> f32 {
+    let mut levels: Vec<f32> = frames.iter().map(|f| f.rms).collect();
+    levels.sort_by(f32::total_cmp);
+    let idx = ((levels.len() as f32 * NOISE_FLOOR_PERCENTILE) as usize).min(levels.len() - 1);
+    levels[idx]
+}
+
+/// RMS level separating speech from background for this recording.
+pub(crate) fn speech_threshold(frames: &[Frame]) -> f32 {
+    let peak = frames.iter().map(|f| f.rms).fold(0.0f32, f32::max);
+    (noise_floor(frames) * NOISE_FLOOR_FACTOR)
+        .min(peak * PEAK_FRACTION)
+        .max(MIN_SPEECH_RMS)
+}
+
+/// Find the sample range that contains speech, including padding at both ends.
+/// Returns `None` if the recording holds less than `MIN_SPEECH_MS` of speech.
+pub fn detect_speech(samples: &[f32], sample_rate: u32) -> Option<Range<usize>> {
+    let frame_len = frame_len(sample_rate);
+    let frames = analyze(samples, frame_len);
+    if frames.is_empty() {
+        return None;
+    }
+
+    let threshold = speech_threshold(&frames);
+    let is_speech =
+        |f: &Frame| f.rms > threshold || (f.rms > threshold * 0.5 && f.zcr > FRICATIVE_ZCR);
+
+    let speech_frames = frames.iter().filter(|f| is_speech(f)).count();
+    if (speech_frames as u32) * FRAME_MS < MIN_SPEECH_MS {
+        return None;
+    }
+
+    let first = frames.iter().position(is_speech)?;
+    let last = frames.iter().rposition(is_speech)?;
+
+    let ms_to_samples = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+    let start = (first * frame_len).saturating_sub(ms_to_samples(PAD_BEFORE_MS));
+    let end = ((last + 1) * frame_len + ms_to_samples(PAD_AFTER_MS)).min(samples.len());
+    Some(start..end)
+}
+
+/// Trim leading and trailing silence, returning the sample range to keep. Fails with
+/// `NoSpeech` if nothing was said, so silent recordings never reach Whisper (which
+/// tends to hallucinate on them).
+pub fn trim_silence(samples: &[f32], sample_rate: u32) -> Result<Range<usize>> {
+    let range = detect_speech(samples, sample_rate).ok_or(VoiceFlowError::NoSpeech)?;
+    log::info!(
+        "VAD kept {} of {} samples ({}ms trimmed)",
+        range.len(),
+        samples.len(),
+        (samples.len() - range.len()) as u64 * 1000 / sample_rate.max(1) as u64
+    );
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    /// Deterministic pseudo-random noise in [-amp, amp].
+    fn noise(len: usize, amp: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * amp
+            })
+            .collect()
+    }
+
+    fn tone(len: usize, freq: f32, amp: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / RATE as f32).sin() * amp)
+            .collect()
+    }
+
+    fn ms(ms: u32) -> usize {
+        (RATE * ms / 1000) as usize
+    }
+
+    #[test]
+    fn test_rejects_digital_silence() {
+        let samples = vec![0.0f32; ms(2000)];
+        assert!(detect_speech(&samples, RATE).is_none());
+        assert!(matches!(
+            trim_silence(&samples, RATE),
+            Err(VoiceFlowError::NoSpeech)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_background_noise_only() {
+        let samples = noise(ms(3000), 0.003, 7);
+        assert!(detect_speech(&samples, RATE).is_none());
+    }
+
+    #[test]
+    fn test_rejects_short_click() {
+        let mut samples = noise(ms(2000), 0.002, 1);
+        for s in &mut samples[ms(1000)..ms(1040)] {
+            *s = 0.8;
+        }
+        assert!(detect_speech(&samples, RATE).is_none());
+    }
+
+    #[test]
+    fn test_trims_leading_and_trailing_silence() {
+        let mut samples = noise(ms(1000), 0.002, 3);
+        samples.extend(tone(ms(1500), 220.0, 0.3));
+        samples.extend(noise(ms(1000), 0.002, 4));
+
+        let range = detect_speech(&samples, RATE).unwrap();
+        let speech_start = ms(1000);
+        let speech_end = ms(2500);
+
+        assert!(range.start <= speech_start, "speech onset was clipped");
+        assert!(range.end >= speech_end, "speech tail was clipped");
+        assert!(range.start >= speech_start - ms(PAD_BEFORE_MS) - ms(FRAME_MS));
+        assert!(range.end <= speech_end + ms(PAD_AFTER_MS) + ms(FRAME_MS));
+        assert_eq!(trim_silence(&samples, RATE).unwrap(), range);
+    }
+
+    #[test]
+    fn test_keeps_quiet_fricative_onset() {
+        // A soft "s" (broadband, high zero-crossing rate) just before a loud vowel
+        let mut samples = noise(ms(800), 0.002, 5);
+        samples.extend(noise(ms(150), 0.007, 6));
+        samples.extend(tone(ms(600), 180.0, 0.4));
+        samples.extend(noise(ms(800), 0.002, 8));
+
+        let range = detect_speech(&samples, RATE).unwrap();
+        assert!(range.start <= ms(800 - PAD_BEFORE_MS + FRAME_MS));
+    }
+
+    #[test]
+    fn test_keeps_speech_without_pauses() {
+        let samples = tone(ms(2000), 300.0, 0.2);
+        assert_eq!(detect_speech(&samples, RATE), Some(0..samples.len()));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(detect_speech(&[], RATE).is_none());
+    }
+}
@@ -0,0 +1,378 @@
+//! Command-line entry point (`voiceflow-cli`). Uses the desktop app's database, so
+//! settings, vocabulary, history and keychain entries are shared. Results go to
+//! stdout and everything else to stderr, so the output can be piped.
+
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::api::refine;
+use crate::audio::capture::AudioState;
+use crate::audio::decoder;
+use crate::commands::pipeline::{self, PipelineResult, PipelineState};
+use crate::commands::subtitles;
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::database::Database;
+use crate::text::replace;
+
+const USAGE: &str = "Usage: voiceflow-cli <command> [options]
+
+Commands:
+  record [--silence <secs>]          Record from the microphone until Enter or <secs> of silence
+                                     after speech (default 2, 0 waits for Enter), then transcribe
+  transcribe <file>...               Transcribe audio files (WAV, FLAC, MP3, Ogg)
+  refine                             Refine the text read from stdin
+  history [--limit <n>] [--search <text>]
+                                     Print saved transcriptions as JSON, newest first
+  subtitles <file>...                Transcribe audio or video files and write .srt and .vtt
+                                     captions next to them
+
+Options:
+  --json  Print record and transcribe results as JSON, one object per line
+
+Recordings and files are refined and saved to history like dictation. API keys come from
+the keychain or the GROQ_API_KEY, OPENAI_API_KEY, DEEPGRAM_API_KEY and OPENAI_REFINE_API_KEY
+environment variables. Warnings and errors are logged to stderr; set RUST_LOG=info or debug
+for more.";
+
+/// `identifier` from tauri.conf.json; the app keeps its data in a directory named after it.
+const APP_IDENTIFIER: &str = "com.voiceflow.app";
+
+/// Quiet after speech that ends a recording, unless `--silence` says otherwise.
+const DEFAULT_SILENCE_SECS: f32 = 2.0;
+/// Input level (RMS) below which the microphone counts as quiet.
+const SILENCE_RMS: f32 = 0.01;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const DEFAULT_HISTORY_LIMIT: i64 = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Record { silence_secs: f32 },
+    Transcribe { files: Vec<String> },
+    Refine,
+    History { limit: i64, search: Option<String> },
+    Subtitles { files: Vec<String> },
+}
+
+impl Command {
+    /// Parse the arguments after the program name, without `--json`. `None` on
+    /// anything unexpected.
+    fn parse(mut args: Vec<String>) -> Option<Self> {
+        if args.is_empty() {
+            return None;
+        }
+        let command = match args.remove(0).as_str() {
+            "record" => {
+                let silence_secs = option(&mut args, "--silence", DEFAULT_SILENCE_SECS)?;
+                if !(silence_secs.is_finite() && silence_secs >= 0.0) {
+                    return None;
+                }
+                Self::Record { silence_secs }
+            }
+            "refine" => Self::Refine,
+            "history" => {
+                let limit = option(&mut args, "--limit", DEFAULT_HISTORY_LIMIT)?;
+                let search = option(&mut args, "--search", String::new())?;
+                Self::History {
+                    limit,
+                    search: Some(search).filter(|s| !s.is_empty()),
+                }
+            }
+            "transcribe" if !args.is_empty() => Self::Transcribe {
+                files: std::mem::take(&mut args),
+            },
+            "subtitles" if !args.is_empty() => Self::Subtitles {
+                files: std::mem::take(&mut args),
+            },
+            _ => return None,
+        };
+        args.is_empty().then_some(command)
+    }
+}
+
+/// Remove `--flag` from `args`, returning whether it was there.
+fn flag(args: &mut Vec<String>, name: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != name);
+    args.len() != before
+}
+
+/// Remove `name <value>` from `args` and parse the value, or `default` when absent.
+/// `None` if the value is missing or malformed.
+fn option<T: FromStr>(args: &mut Vec<String>, name: &str, default: T) -> Option<T> {
+    let Some(i) = args.iter().position(|arg| arg == name) else {
+        return Some(default);
+    };
+    if i + 1 >= args.len() {
+        return None;
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    value.parse().ok()
+}
+
+pub fn run() -> ExitCode {
+    let _ = dotenvy::dotenv();
+    init_logging();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = flag(&mut args, "--json");
+    let Some(command) = Command::parse(args) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    let result = match command {
+        Command::Record { silence_secs } => record(silence_secs, json),
+        Command::Transcribe { files } => transcribe(&files, json),
+        Command::Refine => refine_stdin(),
+        Command::History { limit, search } => history(limit, search.as_deref()),
+        Command::Subtitles { files } => export_subtitles(&files),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("voiceflow-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Log to stderr, keeping stdout for results. `RUST_LOG` picks the level (warn by default).
+fn init_logging() {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(log::LevelFilter::Warn);
+    let _ = fern::Dispatch::new()
+        .level(level)
+        .format(|out, message, record| out.finish(format_args!("[{}] {}", record.level(), message)))
+        .chain(std::io::stderr())
+        .apply();
+}
+
+/// Record from the configured input device until Enter is pressed or, once speech
+/// has been heard, the microphone stays quiet for `silence_secs`.
+fn record(silence_secs: f32, json: bool) -> Result<()> {
+    let db = open_database()?;
+    let input_device = db.get_setting("input_device").ok().flatten();
+    let mut audio = AudioState::new();
+    audio.start_recording(input_device.as_deref())?;
+    eprintln!("Recording... press Enter to stop");
+
+    let enter = enter_pressed();
+    let silence = Duration::from_secs_f32(silence_secs);
+    let mut heard_speech = false;
+    let mut quiet_since = Instant::now();
+    // The stream thread clears `is_recording` if the device fails to start
+    while audio.is_recording() && enter.try_recv().is_err() {
+        std::thread::sleep(POLL_INTERVAL);
+        if audio.get_level() >= SILENCE_RMS {
+            heard_speech = true;
+            quiet_since = Instant::now();
+        } else if heard_speech && !silence.is_zero() && quiet_since.elapsed() >= silence {
+            break;
+        }
+    }
+    let (samples, sample_rate) = audio.stop_recording()?;
+    if samples.is_empty() {
+        return Err(VoiceFlowError::Pipeline("No audio recorded".into()));
+    }
+    let result = process(&Mutex::new(db), &samples, sample_rate)?;
+    print_result(&result, json)
+}
+
+/// Fires once a line is read from stdin. Nothing fires at end of input, so a
+/// recording with stdin redirected stops on silence alone.
+fn enter_pressed() -> mpsc::Receiver<()> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        if matches!(std::io::stdin().read_line(&mut line), Ok(n) if n > 0) {
+            let _ = tx.send(());
+        }
+    });
+    rx
+}
+
+/// Transcribe, refine and save each file, printing results in order.
+fn transcribe(files: &[String], json: bool) -> Result<()> {
+    let db = Mutex::new(open_database()?);
+    for file in files {
+        let (samples, sample_rate) = decoder::decode_file(Path::new(file))?;
+        if samples.is_empty() {
+            return Err(VoiceFlowError::NoSpeech);
+        }
+        let result = process(&db, &samples, sample_rate)?;
+        print_result(&result, json)?;
+    }
+    Ok(())
+}
+
+/// Run audio through the pipeline; the result is saved to history like dictation.
+fn process(db: &Mutex<Database>, samples: &[f32], sample_rate: u32) -> Result<PipelineResult> {
+    tauri::async_runtime::block_on(pipeline::process_headless(
+        db,
+        samples,
+        sample_rate,
+        &show_progress,
+    ))
+}
+
+/// Pipeline steps on stderr, for people watching a terminal.
+fn show_progress(step: PipelineState) {
+    let label = match step {
+        PipelineState::Encoding => "Encoding",
+        PipelineState::Transcribing => "Transcribing",
+        PipelineState::Refining => "Refining",
+        _ => return,
+    };
+    if std::io::stderr().is_terminal() {
+        eprintln!("{}...", label);
+    }
+}
+
+fn print_result(result: &PipelineResult, json: bool) -> Result<()> {
+    if json {
+        println!("{}", to_json(result, false)?);
+    } else {
+        println!("{}", result.refined_text);
+    }
+    Ok(())
+}
+
+/// Refine stdin with the active prompt profile and the replacement rules, as the
+/// pipeline does after transcription. Nothing is saved.
+fn refine_stdin() -> Result<()> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(VoiceFlowError::Pipeline(
+            "Nothing to refine on stdin".into(),
+        ));
+    }
+
+    let db = open_database()?;
+    let (provider, model) = refine::from_settings(&db)?;
+    let profile = refine::active_profile(&db)?;
+    let language = db
+        .get_setting("language")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "pt".to_string());
+    let vocabulary: Vec<String> = db.get_vocabulary()?.into_iter().map(|t| t.term).collect();
+    let replacements = replace::compile_enabled(&db.get_replacement_rules()?);
+
+    let refined = tauri::async_runtime::block_on(refine::refine(
+        provider.as_ref(),
+        &model,
+        &profile,
+        text,
+        &language,
+        &vocabulary,
+    ))?;
+    println!("{}", replace::apply(&refined, &replacements));
+    Ok(())
+}
+
+fn history(limit: i64, search: Option<&str>) -> Result<()> {
+    let db = open_database()?;
+    let transcriptions = db.get_transcriptions(limit, 0, search)?;
+    println!("{}", to_json(&transcriptions, true)?);
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T, pretty: bool) -> Result<String> {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    json.map_err(|e| VoiceFlowError::Pipeline(format!("JSON encoding failed: {}", e)))
+}
+
+/// Print the written caption paths, one per line.
+fn export_subtitles(files: &[String]) -> Result<()> {
+    let db = open_database()?;
+    let (stt, request, format) = subtitles::file_settings(&db)?;
+    for file in files {
+        let written = tauri::async_runtime::block_on(subtitles::export(
+            stt.as_ref(),
+            &request,
+            format,
+            Path::new(file),
+        ))?;
+        println!("{}", written.srt.display());
+        println!("{}", written.vtt.display());
+    }
+    Ok(())
+}
+
+/// The database the desktop app uses (`app_data_dir()/voiceflow.db`).
+fn open_database() -> Result<Database> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| VoiceFlowError::Database("No data directory for this user".into()))?
+        .join(APP_IDENTIFIER);
+    std::fs::create_dir_all(&dir)?;
+    Database::new(&dir.join("voiceflow.db").to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            Command::parse(args("record")),
+            Some(Command::Record { silence_secs: 2.0 })
+        );
+        assert_eq!(
+            Command::parse(args("record --silence 0.5")),
+            Some(Command::Record { silence_secs: 0.5 })
+        );
+        assert_eq!(
+            Command::parse(args("history --search hello --limit 5")),
+            Some(Command::History {
+                limit: 5,
+                search: Some("hello".into())
+            })
+        );
+        assert_eq!(
+            Command::parse(args("transcribe a.wav b.mp3")),
+            Some(Command::Transcribe {
+                files: args("a.wav b.mp3")
+            })
+        );
+        assert_eq!(Command::parse(args("refine")), Some(Command::Refine));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_usage() {
+        for line in [
+            "",
+            "transcribe",
+            "record --silence",
+            "record --silence -1",
+            "history --limit many",
+            "refine extra",
+            "dance",
+        ] {
+            assert_eq!(Command::parse(args(line)), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_flag_is_removed() {
+        let mut list = args("transcribe --json a.wav");
+        assert!(flag(&mut list, "--json"));
+        assert_eq!(list, args("transcribe a.wav"));
+        assert!(!flag(&mut list, "--json"));
+    }
+}
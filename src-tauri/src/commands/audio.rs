@@ -0,0 +1,7 @@
+use crate::audio::capture::{self, InputDevice};
+
+/// List available audio input devices for the device picker in Settings.
+#[tauri::command]
+pub fn list_input_devices() -> std::result::Result<Vec<InputDevice>, String> {
+    capture::list_input_devices().map_err(|e| e.to_string())
+}
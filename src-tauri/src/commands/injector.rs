@@ -1,12 +1,41 @@
 use crate::errors::{Result, VoiceFlowError};
-use arboard::Clipboard;
+use arboard::{Clipboard, ImageData};
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 use std::thread;
 use std::time::Duration;
 
-/// Inject text into the currently focused input field via clipboard paste (Ctrl+V).
-/// Saves and restores clipboard content after injection.
-pub fn inject_text(text: &str) -> Result<()> {
+/// How text reaches the focused application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InjectionMethod {
+    /// Clipboard plus Ctrl+V.
+    #[default]
+    Paste,
+    /// Clipboard plus Ctrl+Shift+V, which terminal emulators use for paste.
+    TerminalPaste,
+    /// Simulated key presses, for apps that block or mangle pasting.
+    Type,
+}
+
+impl InjectionMethod {
+    /// Parse an application rule's `injection` value; unknown values paste.
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("terminal_paste") => Self::TerminalPaste,
+            Some("type") => Self::Type,
+            _ => Self::Paste,
+        }
+    }
+}
+
+/// Inject text into the currently focused input field.
+/// Paste methods save and restore clipboard content after injection.
+pub fn inject_text(text: &str, method: InjectionMethod) -> Result<()> {
+    if method == InjectionMethod::Type {
+        type_text(text)?;
+        log::info!("Text typed ({} chars)", text.len());
+        return Ok(());
+    }
+
     let mut clipboard =
         Clipboard::new().map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
 
@@ -20,8 +49,8 @@ pub fn inject_text(text: &str) -> Result<()> {
 
     thread::sleep(Duration::from_millis(50));
 
-    // Simulate Ctrl+V to paste into the focused field
-    let paste_result = simulate_paste();
+    // Simulate the paste shortcut into the focused field
+    let paste_result = simulate_paste(method == InjectionMethod::TerminalPaste);
 
     // Wait for paste to complete before restoring
     thread::sleep(Duration::from_millis(150));
@@ -38,19 +67,126 @@ pub fn inject_text(text: &str) -> Result<()> {
     Ok(())
 }
 
-fn simulate_paste() -> Result<()> {
+/// Copy the text selected in the focused application by simulating Ctrl+C.
+/// The clipboard is restored afterwards. Returns an empty string if nothing is selected.
+/// Blocks for a moment while the application copies; call it off the async runtime.
+pub fn copy_selection() -> Result<String> {
+    let mut clipboard =
+        Clipboard::new().map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    let previous = SavedClipboard::read(&mut clipboard);
+
+    // Clear old text so an empty selection can't be mistaken for it. Other content
+    // is left alone: reading text from it fails just like from an empty clipboard.
+    if let SavedClipboard::Text(_) = previous {
+        clipboard
+            .set_text(String::new())
+            .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    }
+
+    let copy_result = simulate_copy();
+    // Give the application time to publish the selection
+    thread::sleep(Duration::from_millis(150));
+    let selection = clipboard.get_text().unwrap_or_default();
+
+    previous.restore(&mut clipboard, !selection.is_empty());
+    copy_result?;
+
+    log::info!("Selection copied ({} chars)", selection.len());
+    Ok(selection)
+}
+
+/// Clipboard contents from before a simulated copy.
+enum SavedClipboard {
+    Text(String),
+    Image(ImageData<'static>),
+    /// Empty, or formats arboard cannot read back (e.g. a file list).
+    Other,
+}
+
+impl SavedClipboard {
+    fn read(clipboard: &mut Clipboard) -> Self {
+        if let Ok(text) = clipboard.get_text() {
+            Self::Text(text)
+        } else if let Ok(image) = clipboard.get_image() {
+            Self::Image(image)
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Put the saved contents back. Text is always restored since it was cleared;
+    /// anything else only needs restoring when `overwritten` by the copy.
+    fn restore(self, clipboard: &mut Clipboard, overwritten: bool) {
+        match self {
+            Self::Text(text) => {
+                let _ = clipboard.set_text(text);
+            }
+            Self::Image(image) if overwritten => {
+                let _ = clipboard.set_image(image);
+            }
+            Self::Other if overwritten => {
+                log::warn!("Previous clipboard contents could not be restored");
+            }
+            Self::Image(_) | Self::Other => {}
+        }
+    }
+}
+
+fn simulate_copy() -> Result<()> {
     let mut enigo =
         Enigo::new(&Settings::default()).map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
 
+    // The command hotkey's own modifiers may still be held; Ctrl+Shift+C or Ctrl+Alt+C
+    // mean something else in many apps, so release them first
+    for modifier in [Key::Shift, Key::Alt, Key::Meta] {
+        enigo
+            .key(modifier, Direction::Release)
+            .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    }
     enigo
         .key(Key::Control, Direction::Press)
         .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    enigo
+        .key(Key::Unicode('c'), Direction::Click)
+        .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    enigo
+        .key(Key::Control, Direction::Release)
+        .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+
+    Ok(())
+}
+
+fn simulate_paste(with_shift: bool) -> Result<()> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+
+    enigo
+        .key(Key::Control, Direction::Press)
+        .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    if with_shift {
+        enigo
+            .key(Key::Shift, Direction::Press)
+            .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    }
     enigo
         .key(Key::Unicode('v'), Direction::Click)
         .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    if with_shift {
+        enigo
+            .key(Key::Shift, Direction::Release)
+            .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    }
     enigo
         .key(Key::Control, Direction::Release)
         .map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
 
     Ok(())
 }
+
+fn type_text(text: &str) -> Result<()> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| VoiceFlowError::Injection(e.to_string()))?;
+    enigo
+        .text(text)
+        .map_err(|e| VoiceFlowError::Injection(e.to_string()))
+}
@@ -1,4 +1,6 @@
+pub mod audio;
 pub mod injector;
 pub mod overlay;
 pub mod pipeline;
 pub mod storage;
+pub mod subtitles;
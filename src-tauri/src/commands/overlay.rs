@@ -3,12 +3,15 @@ use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 use crate::AppState;
 
 /// Create and show the floating overlay window at the bottom center of the screen.
+/// The cancel hotkey is active for as long as the overlay is visible.
 pub fn show_overlay(app: &AppHandle) {
     // Don't create if already exists
     if app.get_webview_window("overlay").is_some() {
         return;
     }
 
+    crate::hotkey::bind_cancel_hotkey(app);
+
     let (x, y) = get_overlay_position(app);
 
     match WebviewWindowBuilder::new(app, "overlay", WebviewUrl::App("index.html".into()))
@@ -29,8 +32,10 @@ pub fn show_overlay(app: &AppHandle) {
     }
 }
 
-/// Close the overlay window.
+/// Close the overlay window and release the cancel hotkey.
 pub fn hide_overlay(app: &AppHandle) {
+    crate::hotkey::unbind_cancel_hotkey(app);
+
     if let Some(window) = app.get_webview_window("overlay") {
         let _ = window.close();
         log::info!("Overlay window closed");
@@ -1,12 +1,27 @@
-use crate::api::groq;
-use crate::audio::encoder;
-use crate::commands::injector;
+use crate::api::refine;
+use crate::api::stt::{self, SttProvider, SttRequest, Transcript};
+use crate::audio::capture::AudioState;
+use crate::audio::chunker::{self, Chunk};
+use crate::audio::decoder;
+use crate::audio::encoder::{self, EncodedAudio, UploadFormat};
+use crate::audio::vad;
+use crate::commands::injector::{self, InjectionMethod};
+use crate::context;
 use crate::errors::{Result, VoiceFlowError};
+use crate::hotkey::HotkeyAction;
+use crate::storage::database::Database;
+use crate::text::{replace, spoken, stitch};
 use crate::tray::{self, TrayState};
 use crate::AppState;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
 use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct PipelineResult {
@@ -30,38 +45,314 @@ pub enum PipelineState {
     Refining,
     #[serde(rename = "injecting")]
     Injecting,
+    #[serde(rename = "cancelled")]
+    Cancelled,
     #[serde(rename = "idle")]
     Idle,
 }
 
+/// What the current recording is for, set by the hotkey that started it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RecordingMode {
+    /// Dictate new text at the cursor.
+    #[default]
+    Dictate,
+    /// The speech is an instruction for rewriting `selection`, the text that was
+    /// selected when recording started. The result is pasted over it.
+    Command { selection: String },
+    /// Dictate text that is typed in the `output_language` setting's language.
+    Translate,
+}
+
+/// Cancellation signal for the in-flight pipeline run, shared between
+/// `run_pipeline` and `cancel_pipeline`.
+#[derive(Default)]
+pub struct PipelineCancel {
+    active: AtomicBool,
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl PipelineCancel {
+    /// Mark a new pipeline run as started, clearing any previous cancellation.
+    fn begin(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+        self.active.store(true, Ordering::SeqCst);
+    }
+
+    fn finish(&self) {
+        self.active.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether a pipeline run is in flight.
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Request cancellation. Returns false if no pipeline run is in flight.
+    pub fn cancel(&self) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+        true
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(VoiceFlowError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run `fut` until it completes or the pipeline is cancelled.
+    /// On cancellation the future is dropped, aborting any in-flight HTTP request.
+    async fn guard<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        let notified = self.notify.notified();
+        self.check()?;
+        tokio::select! {
+            result = fut => result,
+            _ = notified => Err(VoiceFlowError::Cancelled),
+        }
+    }
+}
+
 fn emit_state(app: &AppHandle, state: PipelineState) {
     let _ = app.emit("pipeline-state", &state);
 }
 
 #[tauri::command]
 pub async fn start_recording(app: AppHandle) -> std::result::Result<(), String> {
+    begin_recording(&app, HotkeyAction::Dictate)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start recording for `action`. Runs before the overlay appears, while the
+/// target application still has focus.
+pub async fn begin_recording(app: &AppHandle, action: HotkeyAction) -> Result<()> {
     let state = app.state::<AppState>();
-    let mut audio = state.audio.lock().map_err(|e| e.to_string())?;
-    audio.start_recording().map_err(|e| e.to_string())?;
-    emit_state(&app, PipelineState::Recording);
-    tray::update_tray_state(&app, TrayState::Recording);
+    // Refuse before copying the selection: a running recording keeps its own mode
+    {
+        let audio = state
+            .audio
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("Audio lock poisoned: {}", e)))?;
+        ensure_can_record(&state, &audio)?;
+    }
+    // Capture the focused app now: once the overlay shows, it may report itself
+    let window = context::active_window();
+    if let Some(window) = &window {
+        // Titles name documents, e-mails and chat partners; keep them out of the usual log
+        log::info!("Dictating into {}", window.class);
+        log::debug!("Focused window title: {}", window.title);
+    }
+
+    let mode = match action {
+        HotkeyAction::Dictate => RecordingMode::Dictate,
+        HotkeyAction::Command => {
+            let selection = tauri::async_runtime::spawn_blocking(injector::copy_selection)
+                .await
+                .map_err(|e| VoiceFlowError::Injection(format!("Copy task failed: {}", e)))??;
+            if selection.trim().is_empty() {
+                return Err(VoiceFlowError::Pipeline(
+                    "Select the text to rewrite before using command mode".into(),
+                ));
+            }
+            RecordingMode::Command { selection }
+        }
+        HotkeyAction::Translate => RecordingMode::Translate,
+    };
+
+    let input_device = {
+        let db = state
+            .db
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
+        db.get_setting("input_device").ok().flatten()
+    };
+    let mut audio = state
+        .audio
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("Audio lock poisoned: {}", e)))?;
+    // Checked again under the audio lock, which `process_file` also holds while
+    // claiming the pipeline, in case another run started while the selection was copied
+    ensure_can_record(&state, &audio)?;
+    *state
+        .focus
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("Focus lock poisoned: {}", e)))? = window;
+    *state
+        .mode
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("Mode lock poisoned: {}", e)))? = mode;
+    if audio.start_recording(input_device.as_deref())? {
+        // The saved microphone is unplugged; tell the user which one is missing
+        let _ = app.emit("input-device-fallback", input_device.unwrap_or_default());
+    }
+    emit_state(app, PipelineState::Recording);
+    tray::update_tray_state(app, TrayState::Recording);
     Ok(())
 }
 
+/// `check_can_record` for the app's current state. Call with the audio lock held.
+fn ensure_can_record(state: &AppState, audio: &AudioState) -> Result<()> {
+    check_can_record(
+        audio.is_recording(),
+        &state.cancel,
+        state.file_job.load(Ordering::SeqCst),
+    )
+}
+
+/// Refuse a new recording while one is running, an earlier one is still processing or
+/// a file is being transcribed. Runs share the recording mode, cancel slot, overlay and
+/// tray until they end, so an overlapping run would take them over.
+fn check_can_record(recording: bool, cancel: &PipelineCancel, file_job: bool) -> Result<()> {
+    if recording {
+        return Err(VoiceFlowError::Pipeline("Already recording".into()));
+    }
+    if file_job {
+        return Err(VoiceFlowError::Pipeline(
+            "Wait for the audio file to finish transcribing".into(),
+        ));
+    }
+    if cancel.is_active() {
+        return Err(VoiceFlowError::Pipeline(
+            "Wait for the previous dictation to finish processing".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Start recording for `action` from outside the main window (hotkeys, the local
+/// API), showing the overlay and its level meter.
+pub async fn begin_recording_with_overlay(app: &AppHandle, action: HotkeyAction) -> Result<()> {
+    begin_recording(app, action).await?;
+    super::overlay::show_overlay(app);
+    super::overlay::start_audio_level_emitter(app.clone());
+    Ok(())
+}
+
+/// Whether the microphone is currently being recorded.
+pub fn is_recording(app: &AppHandle) -> Result<bool> {
+    let state = app.state::<AppState>();
+    let audio = state
+        .audio
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("Audio lock poisoned: {}", e)))?;
+    Ok(audio.is_recording())
+}
+
 #[tauri::command]
 pub async fn stop_and_process(app: AppHandle) -> std::result::Result<PipelineResult, String> {
-    let result = run_pipeline(&app).await;
-    if let Ok(r) = &result {
-        let _ = app.emit("pipeline-complete", r);
+    process_recording(&app).await.map_err(|e| e.to_string())
+}
+
+/// Abort the current recording (discarding the audio) or the in-flight processing
+/// (skipping injection). No-op if nothing is running.
+#[tauri::command]
+pub async fn cancel_pipeline(app: AppHandle) -> std::result::Result<(), String> {
+    cancel(&app).map_err(|e| e.to_string())
+}
+
+/// Transcribe an audio file (WAV, FLAC, MP3, Ogg) through the same STT, refinement
+/// and history steps as a recording. Nothing is injected.
+#[tauri::command]
+pub async fn transcribe_file(
+    app: AppHandle,
+    path: String,
+) -> std::result::Result<PipelineResult, String> {
+    process_file(&app, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop recording, run the pipeline, then reset overlay, tray and pipeline state.
+pub async fn process_recording(app: &AppHandle) -> Result<PipelineResult> {
+    let state = app.state::<AppState>();
+    let cancel = &state.cancel;
+    cancel.begin();
+    let result = run_pipeline(app, cancel).await;
+    cancel.finish();
+    report(app, &result);
+    result
+}
+
+/// Run an audio file through the pipeline, then reset tray and pipeline state.
+/// Refused while recording or processing: a file run takes over the pipeline's
+/// state, cancellation and tray until it ends.
+pub async fn process_file(app: &AppHandle, path: &Path) -> Result<PipelineResult> {
+    let state = app.state::<AppState>();
+    let cancel = &state.cancel;
+    {
+        let audio = state
+            .audio
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("Audio lock poisoned: {}", e)))?;
+        if audio.is_recording() || cancel.is_active() {
+            return Err(VoiceFlowError::Pipeline(
+                "Finish the current dictation before transcribing a file".into(),
+            ));
+        }
+        state.file_job.store(true, Ordering::SeqCst);
+        cancel.begin();
+    }
+    let result = run_file_pipeline(app, cancel, path).await;
+    cancel.finish();
+    state.file_job.store(false, Ordering::SeqCst);
+    report(app, &result);
+    result
+}
+
+/// Announce how a pipeline run ended and go back to idle.
+fn report(app: &AppHandle, result: &Result<PipelineResult>) {
+    match result {
+        Ok(r) => {
+            let _ = app.emit("pipeline-complete", r);
+        }
+        Err(VoiceFlowError::Cancelled) => {
+            log::info!("Pipeline cancelled");
+            emit_state(app, PipelineState::Cancelled);
+        }
+        Err(_) => {}
     }
     // Hide overlay before going idle so the pill never shows empty
-    super::overlay::hide_overlay(&app);
-    tray::update_tray_state(&app, TrayState::Idle);
-    emit_state(&app, PipelineState::Idle);
-    result.map_err(|e| e.to_string())
+    super::overlay::hide_overlay(app);
+    tray::update_tray_state(app, TrayState::Idle);
+    emit_state(app, PipelineState::Idle);
 }
 
-async fn run_pipeline(app: &AppHandle) -> Result<PipelineResult> {
+/// Cancel whatever the pipeline is doing. While recording, the audio is discarded
+/// here directly; while processing, `run_pipeline` notices the signal and bails out.
+pub fn cancel(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let was_recording = {
+        let mut audio = state
+            .audio
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("Audio lock poisoned: {}", e)))?;
+        if audio.is_recording() {
+            audio.cancel_recording()?;
+            true
+        } else {
+            false
+        }
+    };
+
+    if was_recording {
+        emit_state(app, PipelineState::Cancelled);
+        super::overlay::hide_overlay(app);
+        tray::update_tray_state(app, TrayState::Idle);
+        emit_state(app, PipelineState::Idle);
+    } else if !state.cancel.cancel() {
+        log::info!("Cancel requested but nothing is running");
+    }
+    Ok(())
+}
+
+async fn run_pipeline(app: &AppHandle, cancel: &PipelineCancel) -> Result<PipelineResult> {
     let t_start = Instant::now();
 
     // 1. Stop recording and get samples
@@ -78,86 +369,425 @@ async fn run_pipeline(app: &AppHandle) -> Result<PipelineResult> {
         return Err(VoiceFlowError::Pipeline("No audio recorded".into()));
     }
 
-    // 2. Encode WAV
-    emit_state(app, PipelineState::Encoding);
+    let job = {
+        let state = app.state::<AppState>();
+        let mode = std::mem::take(
+            &mut *state
+                .mode
+                .lock()
+                .map_err(|e| VoiceFlowError::Pipeline(format!("Mode lock poisoned: {}", e)))?,
+        );
+        let window = state
+            .focus
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("Focus lock poisoned: {}", e)))?
+            .clone();
+        Job {
+            mode,
+            window,
+            inject: true,
+            timings: false,
+        }
+    };
     tray::update_tray_state(app, TrayState::Processing);
-    let wav_data = encoder::encode_wav(&samples, sample_rate)?;
+    let state = app.state::<AppState>();
+    let progress = |step| emit_state(app, step);
+    process_audio(
+        &state.db,
+        cancel,
+        &progress,
+        &samples,
+        sample_rate,
+        job,
+        t_start,
+    )
+    .await
+}
 
-    // 3. Get settings - API key always from keychain, other settings from DB
-    let api_key = crate::keychain::get_api_key().unwrap_or_default();
+async fn run_file_pipeline(
+    app: &AppHandle,
+    cancel: &PipelineCancel,
+    path: &Path,
+) -> Result<PipelineResult> {
+    let t_start = Instant::now();
 
-    if api_key.is_empty() {
-        return Err(VoiceFlowError::Pipeline(
-            "No API key configured. Set it in Settings.".into(),
-        ));
+    // 1. Decode the file
+    emit_state(app, PipelineState::Encoding);
+    tray::update_tray_state(app, TrayState::Processing);
+    log::info!("Transcribing file {}", path.display());
+    let (samples, sample_rate) = cancel.guard(decode(path)).await?;
+    if samples.is_empty() {
+        return Err(VoiceFlowError::NoSpeech);
     }
 
-    let (stt_model, llm_model, language, raw_mode) = {
-        let state = app.state::<AppState>();
-        let db = state
-            .db
+    let job = Job {
+        mode: RecordingMode::Dictate,
+        window: None,
+        inject: false,
+        timings: true,
+    };
+    let state = app.state::<AppState>();
+    let progress = |step| emit_state(app, step);
+    process_audio(
+        &state.db,
+        cancel,
+        &progress,
+        &samples,
+        sample_rate,
+        job,
+        t_start,
+    )
+    .await
+}
+
+/// Run audio through STT, refinement and history outside the desktop app, as the
+/// command-line tool does: no events, tray or injection. `progress` hears each step.
+pub async fn process_headless(
+    db: &Mutex<Database>,
+    samples: &[f32],
+    sample_rate: u32,
+    progress: &(dyn Fn(PipelineState) + Send + Sync),
+) -> Result<PipelineResult> {
+    let job = Job {
+        mode: RecordingMode::Dictate,
+        window: None,
+        inject: false,
+        timings: true,
+    };
+    let cancel = PipelineCancel::default();
+    process_audio(
+        db,
+        &cancel,
+        progress,
+        samples,
+        sample_rate,
+        job,
+        Instant::now(),
+    )
+    .await
+}
+
+/// What to do with a piece of audio besides transcribing it.
+struct Job {
+    mode: RecordingMode,
+    /// Window focused when recording started; picks the app rule.
+    window: Option<context::ActiveWindow>,
+    /// Type the result into the focused app. Files are only stored.
+    inject: bool,
+    /// Store segment and word timings. Only worth the slower STT response for files.
+    timings: bool,
+}
+
+/// Steps 2-8 of the pipeline, shared by recordings, files and the command line.
+async fn process_audio(
+    db: &Mutex<Database>,
+    cancel: &PipelineCancel,
+    progress: &(dyn Fn(PipelineState) + Send + Sync),
+    samples: &[f32],
+    sample_rate: u32,
+    job: Job,
+    t_start: Instant,
+) -> Result<PipelineResult> {
+    let Job {
+        mode,
+        window,
+        inject,
+        timings,
+    } = job;
+
+    // 2. Trim silence (unless disabled) and encode for upload; silent recordings stop here
+    progress(PipelineState::Encoding);
+    let (vad_enabled, upload_format, stt, stt_model) = {
+        let db = db
             .lock()
             .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
-        let stt_model = db
-            .get_setting("stt_model")
+        let vad_enabled = db
+            .get_setting("vad")
             .ok()
             .flatten()
-            .unwrap_or_else(|| "whisper-large-v3".to_string());
-        let llm_model = db
-            .get_setting("llm_model")
-            .ok()
-            .flatten()
-            .unwrap_or_else(|| "llama-3.3-70b-versatile".to_string());
-        let language = db
-            .get_setting("language")
-            .ok()
-            .flatten()
-            .unwrap_or_else(|| "pt".to_string());
+            .map(|v| v != "false")
+            .unwrap_or(true);
+        let upload_format = db.get_setting("upload_format").ok().flatten();
+        let (stt, stt_model) = stt::from_settings(&db)?;
+        let upload_format = stt
+            .preferred_format()
+            .unwrap_or_else(|| UploadFormat::from_setting(upload_format.as_deref()));
+        (vad_enabled, upload_format, stt, stt_model)
+    };
+    let speech_range = if vad_enabled {
+        vad::trim_silence(samples, sample_rate)?
+    } else {
+        0..samples.len()
+    };
+    let speech = &samples[speech_range.clone()];
+    // Long recordings are split so each request stays within the API upload limit
+    let chunks = chunker::split(speech, sample_rate);
+    // Where each chunk starts in the recording, so segment times survive the trim
+    let offsets_ms: Vec<u64> = chunks
+        .iter()
+        .map(|chunk| {
+            (speech_range.start + chunk.range.start) as u64 * 1000 / sample_rate.max(1) as u64
+        })
+        .collect();
+    let encoded = chunks
+        .iter()
+        .map(|chunk| encoder::encode(&speech[chunk.range.clone()], sample_rate, upload_format))
+        .collect::<Result<Vec<_>>>()?;
+    log::info!(
+        "Encoded {} chunk(s), {} bytes as {}",
+        encoded.len(),
+        encoded.iter().map(|a| a.data.len()).sum::<usize>(),
+        upload_format.mime_type()
+    );
+
+    // 3. Get settings - API keys always from keychain, other settings from DB
+    let (
+        app_name,
+        refiner,
+        spoken_commands,
+        language,
+        translation,
+        vocabulary,
+        replacements,
+        injection,
+    ) = {
+        let db = db
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
+        // A rule for the focused app overrides the global profile, language and injection
+        let app_rules = db.get_app_rules()?;
+        let rule = window
+            .as_ref()
+            .and_then(|window| context::rules::find_match(&app_rules, window));
+        if let (Some(rule), Some(window)) = (rule, &window) {
+            log::info!("Applying app rule {} for {}", rule.id, window.class);
+        }
+        let language = match rule.and_then(|r| r.language.clone()) {
+            Some(language) => language,
+            None => db
+                .get_setting("language")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "pt".to_string()),
+        };
+        // Whisper can translate into English by itself; other targets go through the LLM
+        let translation = match mode {
+            RecordingMode::Translate => {
+                let target = db
+                    .get_setting("output_language")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| "en".to_string());
+                if target == language {
+                    Translation::None
+                } else if target == "en" && stt.supports_translation(&stt_model) {
+                    Translation::Whisper
+                } else {
+                    Translation::Llm(target)
+                }
+            }
+            _ => Translation::None,
+        };
         let raw_mode = db
             .get_setting("raw_mode")
             .ok()
             .flatten()
             .map(|v| v == "true")
             .unwrap_or(false);
-        (stt_model, llm_model, language, raw_mode)
+        // Raw mode needs no LLM, so a missing refinement key is not an error then.
+        // Command mode and LLM translation always do.
+        let needs_llm = matches!(mode, RecordingMode::Command { .. })
+            || matches!(translation, Translation::Llm(_));
+        let refiner = if raw_mode && !needs_llm {
+            None
+        } else {
+            let (provider, model) = refine::from_settings(&db)?;
+            let profile = match rule.and_then(|r| r.profile_id.as_deref()) {
+                Some(id) => refine::profile_by_id(&db, id)?,
+                None => refine::active_profile(&db)?,
+            };
+            Some((provider, model, profile))
+        };
+        let spoken_commands = db
+            .get_setting("spoken_commands")
+            .ok()
+            .flatten()
+            .map(|v| v != "false")
+            .unwrap_or(true);
+        let vocabulary = db
+            .get_vocabulary()?
+            .into_iter()
+            .map(|t| t.term)
+            .collect::<Vec<_>>();
+        let replacements = replace::compile_enabled(&db.get_replacement_rules()?);
+        let injection = InjectionMethod::from_setting(rule.and_then(|r| r.injection.as_deref()));
+        (
+            window.map(|w| w.class),
+            refiner,
+            spoken_commands,
+            language,
+            translation,
+            vocabulary,
+            replacements,
+            injection,
+        )
     };
 
     // 4. Transcribe
-    emit_state(app, PipelineState::Transcribing);
+    progress(PipelineState::Transcribing);
     let t_stt = Instant::now();
-    let raw_text = groq::transcribe(&api_key, wav_data, &stt_model, &language).await?;
+    let request = SttRequest {
+        model: stt_model,
+        language: language.clone(),
+        vocabulary,
+        translate: translation == Translation::Whisper,
+        timings,
+    };
+    log::info!(
+        "{} with {} ({})",
+        if request.translate {
+            "Translating"
+        } else {
+            "Transcribing"
+        },
+        stt.name(),
+        request.model
+    );
+    let mut transcript = cancel
+        .guard(transcribe_chunks(
+            stt.as_ref(),
+            &request,
+            &chunks,
+            &offsets_ms,
+            encoded,
+        ))
+        .await?;
     let stt_latency = t_stt.elapsed().as_millis() as u64;
+    let raw_text = std::mem::take(&mut transcript.text);
 
     if raw_text.is_empty() {
         return Err(VoiceFlowError::Pipeline("Empty transcription".into()));
     }
+    // With `auto`, the rest of the pipeline uses whatever the provider detected
+    let language = match transcript.language.take() {
+        Some(detected) if request.detect_language() => {
+            log::info!("Detected language: {}", detected);
+            detected
+        }
+        _ => language,
+    };
+    // Whisper's translation is already English, so the later steps treat it as such
+    let text_language = if request.translate { "en" } else { &language };
 
-    // 5. Refine with LLM (skip if raw mode enabled)
-    let (refined_text, llm_latency) = if raw_mode {
-        log::info!("Raw mode enabled — skipping LLM refinement");
-        (raw_text.clone(), 0u64)
-    } else {
-        emit_state(app, PipelineState::Refining);
-        let t_llm = Instant::now();
-        let text = groq::refine(&api_key, &raw_text, &llm_model, &language).await?;
-        let latency = t_llm.elapsed().as_millis() as u64;
-        (text, latency)
+    // 5. Refine with LLM (skip if raw mode enabled), translate, or apply a spoken command
+    let (mut refined_text, llm_latency) = match (&mode, &translation, refiner) {
+        (RecordingMode::Command { selection }, _, Some((refiner, llm_model, _))) => {
+            progress(PipelineState::Refining);
+            log::info!(
+                "Rewriting {} selected chars with {} ({})",
+                selection.len(),
+                refiner.name(),
+                llm_model
+            );
+            let t_llm = Instant::now();
+            let text = cancel
+                .guard(refine::rewrite(
+                    refiner.as_ref(),
+                    &llm_model,
+                    &raw_text,
+                    selection,
+                ))
+                .await?;
+            let latency = t_llm.elapsed().as_millis() as u64;
+            (text, latency)
+        }
+        // A detected language can turn out to be the target already; that is plain refinement
+        (_, Translation::Llm(target), Some((refiner, llm_model, _))) if *target != language => {
+            progress(PipelineState::Refining);
+            log::info!(
+                "Translating {} -> {} with {} ({})",
+                language,
+                target,
+                refiner.name(),
+                llm_model
+            );
+            let t_llm = Instant::now();
+            let text = cancel
+                .guard(refine::translate(
+                    refiner.as_ref(),
+                    &llm_model,
+                    &raw_text,
+                    &language,
+                    target,
+                    &request.vocabulary,
+                ))
+                .await?;
+            let latency = t_llm.elapsed().as_millis() as u64;
+            (text, latency)
+        }
+        (_, _, None) => {
+            log::info!("Raw mode enabled — skipping LLM refinement");
+            // Without an LLM, spoken "comma" / "new line" would be typed literally
+            let text = if spoken_commands {
+                spoken::apply(&raw_text, text_language)
+            } else {
+                raw_text.clone()
+            };
+            (text, 0u64)
+        }
+        (_, _, Some((refiner, llm_model, profile))) => {
+            progress(PipelineState::Refining);
+            log::info!(
+                "Refining with {} ({}, profile \"{}\")",
+                refiner.name(),
+                profile.model.as_deref().unwrap_or(&llm_model),
+                profile.name
+            );
+            let t_llm = Instant::now();
+            let text = cancel
+                .guard(refine::refine(
+                    refiner.as_ref(),
+                    &llm_model,
+                    &profile,
+                    &raw_text,
+                    text_language,
+                    &request.vocabulary,
+                ))
+                .await?;
+            let latency = t_llm.elapsed().as_millis() as u64;
+            (text, latency)
+        }
     };
 
-    // 6. Inject text into the currently focused input field
-    emit_state(app, PipelineState::Injecting);
-    injector::inject_text(&refined_text)?;
+    // 6. Apply the user's replacement rules
+    if !replacements.is_empty() {
+        refined_text = replace::apply(&refined_text, &replacements);
+    }
+
+    // 7. Inject text into the currently focused input field (last chance to cancel).
+    // In command mode the selection is still active, so pasting replaces it.
+    cancel.check()?;
+    if inject {
+        progress(PipelineState::Injecting);
+        injector::inject_text(&refined_text, injection)?;
+    }
 
     let total_latency = t_start.elapsed().as_millis() as u64;
 
-    // 7. Save to database (log errors instead of silently ignoring)
+    // 8. Save to database (log errors instead of silently ignoring)
     {
-        let state = app.state::<AppState>();
-        let db_result = state.db.lock();
-        if let Ok(db) = db_result {
-            if let Err(e) =
-                db.save_transcription(&raw_text, &refined_text, stt_latency, llm_latency)
-            {
+        if let Ok(db) = db.lock() {
+            let saved = db
+                .save_transcription(
+                    &raw_text,
+                    &refined_text,
+                    stt_latency,
+                    llm_latency,
+                    app_name.as_deref(),
+                    (language != stt::AUTO_LANGUAGE).then_some(language.as_str()),
+                )
+                .and_then(|id| db.save_segments(&id, &transcript.segments));
+            if let Err(e) = saved {
                 log::error!("Failed to save transcription to DB: {}", e);
             }
         }
@@ -180,3 +810,117 @@ async fn run_pipeline(app: &AppHandle) -> Result<PipelineResult> {
 
     Ok(result)
 }
+
+/// How a translation-mode recording reaches the output language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Translation {
+    /// Not translating: dictation, command mode, or already speaking the target.
+    None,
+    /// Whisper's translate task, English output only.
+    Whisper,
+    /// The refinement LLM translates into this language.
+    Llm(String),
+}
+
+/// Upper bound on simultaneous STT requests, to stay clear of API rate limits.
+const MAX_CONCURRENT_TRANSCRIPTIONS: usize = 4;
+
+/// Decode an audio or video file and transcribe it like a recording, minus the
+/// silence trimming, so segment times match the file.
+pub async fn transcribe_path(
+    stt: &dyn SttProvider,
+    request: &SttRequest,
+    format: UploadFormat,
+    path: &Path,
+) -> Result<Transcript> {
+    let (samples, sample_rate) = decode(path).await?;
+    if samples.is_empty() {
+        return Err(VoiceFlowError::NoSpeech);
+    }
+
+    let chunks = chunker::split(&samples, sample_rate);
+    let offsets_ms: Vec<u64> = chunks
+        .iter()
+        .map(|chunk| chunk.range.start as u64 * 1000 / sample_rate.max(1) as u64)
+        .collect();
+    let encoded = chunks
+        .iter()
+        .map(|chunk| encoder::encode(&samples[chunk.range.clone()], sample_rate, format))
+        .collect::<Result<Vec<_>>>()?;
+    log::info!(
+        "Transcribing {} in {} chunk(s) with {} ({})",
+        path.display(),
+        encoded.len(),
+        stt.name(),
+        request.model
+    );
+    transcribe_chunks(stt, request, &chunks, &offsets_ms, encoded).await
+}
+
+/// Decode an audio file off the async runtime; long files take a while.
+async fn decode(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let path = path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || decoder::decode_file(&path))
+        .await
+        .map_err(|e| VoiceFlowError::Audio(format!("Decoding task failed: {}", e)))?
+}
+
+/// Transcribe every chunk concurrently and stitch the transcripts back together in order.
+/// The detected language is the first chunk's that reports one. Segment times are
+/// moved onto the recording's timeline using each chunk's offset in `offsets_ms`.
+async fn transcribe_chunks(
+    stt: &dyn SttProvider,
+    request: &SttRequest,
+    chunks: &[Chunk],
+    offsets_ms: &[u64],
+    encoded: Vec<EncodedAudio>,
+) -> Result<Transcript> {
+    let transcripts: Vec<Transcript> = stream::iter(encoded)
+        .map(|audio| stt.transcribe(audio, request))
+        .buffered(MAX_CONCURRENT_TRANSCRIPTIONS)
+        .try_collect()
+        .await?;
+
+    let language = transcripts.iter().find_map(|t| t.language.clone());
+    let (texts, segments): (Vec<_>, Vec<_>) = transcripts
+        .into_iter()
+        .map(|t| (t.text, t.segments))
+        .unzip();
+    let overlaps = chunks.iter().map(|chunk| chunk.overlaps_previous);
+    let text = stitch::stitch(texts.into_iter().zip(overlaps.clone()));
+    let segments = stitch::stitch_segments(
+        segments
+            .into_iter()
+            .zip(offsets_ms.iter().copied())
+            .zip(overlaps)
+            .map(|((segments, offset), overlaps)| (segments, offset, overlaps)),
+    );
+    Ok(Transcript {
+        text,
+        language,
+        segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_refused_while_previous_run_processes() {
+        let cancel = PipelineCancel::default();
+        assert!(check_can_record(false, &cancel, false).is_ok());
+        // A second start must not replace the running recording's mode
+        assert!(check_can_record(true, &cancel, false).is_err());
+
+        // Run A is transcribing; run B must not start and take over its cancel slot
+        cancel.begin();
+        assert!(check_can_record(false, &cancel, false).is_err());
+        assert!(cancel.cancel());
+        assert!(matches!(cancel.check(), Err(VoiceFlowError::Cancelled)));
+
+        cancel.finish();
+        assert!(check_can_record(false, &cancel, false).is_ok());
+        assert!(check_can_record(false, &cancel, true).is_err());
+    }
+}
@@ -1,4 +1,10 @@
-use crate::storage::models::{Transcription, TranscriptionStats};
+use crate::api::refine::{self, BUILTIN_PROFILE_ID};
+use crate::context::ActiveWindow;
+use crate::storage::models::{
+    AppRule, PromptProfile, ReplacementRule, RuleKind, Segment, Transcription, TranscriptionStats,
+    VocabularyTerm,
+};
+use crate::text::replace;
 use crate::AppState;
 use tauri::State;
 
@@ -27,6 +33,16 @@ pub fn delete_transcription(
     db.delete_transcription(&id).map_err(|e| e.to_string())
 }
 
+/// Segment and word timings of a transcription, for seeking and subtitles.
+#[tauri::command]
+pub fn get_transcription_segments(
+    state: State<'_, AppState>,
+    id: String,
+) -> std::result::Result<Vec<Segment>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_segments(&id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_stats(state: State<'_, AppState>) -> std::result::Result<TranscriptionStats, String> {
     let db = state.db.lock().map_err(|e| e.to_string())?;
@@ -51,3 +67,198 @@ pub fn set_setting(
     let db = state.db.lock().map_err(|e| e.to_string())?;
     db.set_setting(&key, &value).map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub fn get_vocabulary(
+    state: State<'_, AppState>,
+) -> std::result::Result<Vec<VocabularyTerm>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_vocabulary().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_vocabulary_term(
+    state: State<'_, AppState>,
+    term: String,
+) -> std::result::Result<String, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_vocabulary_term(&term).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_vocabulary_term(
+    state: State<'_, AppState>,
+    id: String,
+) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.delete_vocabulary_term(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_replacement_rules(
+    state: State<'_, AppState>,
+) -> std::result::Result<Vec<ReplacementRule>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_replacement_rules().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_replacement_rule(
+    state: State<'_, AppState>,
+    pattern: String,
+    replacement: String,
+    kind: RuleKind,
+    case_insensitive: bool,
+) -> std::result::Result<ReplacementRule, String> {
+    replace::build_regex(&pattern, kind, case_insensitive).map_err(|e| e.to_string())?;
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_replacement_rule(&pattern, &replacement, kind, case_insensitive)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_replacement_rule(
+    state: State<'_, AppState>,
+    rule: ReplacementRule,
+) -> std::result::Result<(), String> {
+    replace::build_regex(&rule.pattern, rule.kind, rule.case_insensitive)
+        .map_err(|e| e.to_string())?;
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.update_replacement_rule(&rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_replacement_rule(
+    state: State<'_, AppState>,
+    id: String,
+) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.delete_replacement_rule(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reorder_replacement_rules(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.reorder_replacement_rules(&ids).map_err(|e| e.to_string())
+}
+
+/// The built-in profile followed by the user's own.
+#[tauri::command]
+pub fn get_prompt_profiles(
+    state: State<'_, AppState>,
+) -> std::result::Result<Vec<PromptProfile>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let mut profiles = vec![refine::builtin_profile()];
+    profiles.extend(db.get_prompt_profiles().map_err(|e| e.to_string())?);
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn add_prompt_profile(
+    state: State<'_, AppState>,
+    name: String,
+    system_prompt: String,
+    temperature: f32,
+    model: Option<String>,
+) -> std::result::Result<PromptProfile, String> {
+    let model = model.filter(|m| !m.trim().is_empty());
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_prompt_profile(&name, &system_prompt, temperature, model.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_prompt_profile(
+    state: State<'_, AppState>,
+    mut profile: PromptProfile,
+) -> std::result::Result<(), String> {
+    if profile.id == BUILTIN_PROFILE_ID {
+        return Err("The default prompt profile cannot be changed".into());
+    }
+    profile.model = profile.model.filter(|m| !m.trim().is_empty());
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.update_prompt_profile(&profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_prompt_profile(
+    state: State<'_, AppState>,
+    id: String,
+) -> std::result::Result<(), String> {
+    if id == BUILTIN_PROFILE_ID {
+        return Err("The default prompt profile cannot be deleted".into());
+    }
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.delete_prompt_profile(&id).map_err(|e| e.to_string())?;
+    // Don't leave the setting pointing at a profile that no longer exists
+    if db.get_setting("prompt_profile").ok().flatten().as_deref() == Some(id.as_str()) {
+        db.set_setting("prompt_profile", BUILTIN_PROFILE_ID)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_app_rules(state: State<'_, AppState>) -> std::result::Result<Vec<AppRule>, String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.get_app_rules().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_app_rule(
+    state: State<'_, AppState>,
+    window_class: String,
+    window_title: String,
+    profile_id: Option<String>,
+    language: Option<String>,
+    injection: Option<String>,
+) -> std::result::Result<AppRule, String> {
+    if window_class.trim().is_empty() && window_title.trim().is_empty() {
+        return Err("An app rule needs a window class or title to match".into());
+    }
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.add_app_rule(
+        window_class.trim(),
+        window_title.trim(),
+        profile_id.as_deref(),
+        language.as_deref(),
+        injection.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_app_rule(
+    state: State<'_, AppState>,
+    rule: AppRule,
+) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.update_app_rule(&rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_app_rule(state: State<'_, AppState>, id: String) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.delete_app_rule(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reorder_app_rules(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> std::result::Result<(), String> {
+    let db = state.db.lock().map_err(|e| e.to_string())?;
+    db.reorder_app_rules(&ids).map_err(|e| e.to_string())
+}
+
+/// The window focused when the last recording started, to help write rules.
+#[tauri::command]
+pub fn get_active_window(
+    state: State<'_, AppState>,
+) -> std::result::Result<Option<ActiveWindow>, String> {
+    let focus = state.focus.lock().map_err(|e| e.to_string())?;
+    Ok(focus.clone())
+}
@@ -0,0 +1,98 @@
+//! Caption files for audio and video recordings.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::api::stt::{self, SttProvider, SttRequest};
+use crate::audio::encoder::UploadFormat;
+use crate::commands::pipeline;
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::database::Database;
+use crate::text::subtitles::{self, SubtitleFormat};
+use crate::AppState;
+
+/// Where the captions were written.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleFiles {
+    pub srt: PathBuf,
+    pub vtt: PathBuf,
+    pub cues: usize,
+}
+
+/// Transcribe `path` and write `.srt` and `.vtt` files next to it.
+#[tauri::command]
+pub async fn export_subtitles(
+    app: AppHandle,
+    path: String,
+) -> std::result::Result<SubtitleFiles, String> {
+    let (stt, request, format) = {
+        let state = app.state::<AppState>();
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        file_settings(&db).map_err(|e| e.to_string())?
+    };
+    export(stt.as_ref(), &request, format, Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// STT provider, request and upload format for transcribing a file, from the same
+/// settings dictation uses.
+pub fn file_settings(db: &Database) -> Result<(Box<dyn SttProvider>, SttRequest, UploadFormat)> {
+    let (stt, model) = stt::from_settings(db)?;
+    let language = db
+        .get_setting("language")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "pt".to_string());
+    let vocabulary = db.get_vocabulary()?.into_iter().map(|t| t.term).collect();
+    let format = stt.preferred_format().unwrap_or_else(|| {
+        UploadFormat::from_setting(db.get_setting("upload_format").ok().flatten().as_deref())
+    });
+    let request = SttRequest {
+        model,
+        language,
+        vocabulary,
+        translate: false,
+        timings: true,
+    };
+    Ok((stt, request, format))
+}
+
+/// Transcribe an audio or video file and write its captions as SubRip and WebVTT.
+pub async fn export(
+    stt: &dyn SttProvider,
+    request: &SttRequest,
+    format: UploadFormat,
+    path: &Path,
+) -> Result<SubtitleFiles> {
+    let transcript = pipeline::transcribe_path(stt, request, format, path).await?;
+    if transcript.segments.is_empty() {
+        return Err(VoiceFlowError::Pipeline(format!(
+            "{} ({}) returned no timestamps; subtitles need a Whisper or Deepgram model",
+            stt.name(),
+            request.model
+        )));
+    }
+
+    let cues = subtitles::cues(&transcript.segments);
+    let srt = write(path, &cues, SubtitleFormat::Srt)?;
+    let vtt = write(path, &cues, SubtitleFormat::Vtt)?;
+    log::info!(
+        "Wrote {} cues to {} and {}",
+        cues.len(),
+        srt.display(),
+        vtt.display()
+    );
+    Ok(SubtitleFiles {
+        srt,
+        vtt,
+        cues: cues.len(),
+    })
+}
+
+fn write(source: &Path, cues: &[subtitles::Cue], format: SubtitleFormat) -> Result<PathBuf> {
+    let path = source.with_extension(format.extension());
+    std::fs::write(&path, subtitles::render(cues, format))?;
+    Ok(path)
+}
@@ -0,0 +1,34 @@
+//! The application the user is dictating into, and per-application rules.
+
+pub mod rules;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use serde::Serialize;
+
+/// The focused window when dictation started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ActiveWindow {
+    /// Application class (WM_CLASS on X11), e.g. "Slack" or "org.wezfurlong.wezterm".
+    pub class: String,
+    pub title: String,
+}
+
+/// Look up the focused window. Returns `None` where detection is unsupported
+/// (Wayland-only sessions, Windows, macOS) or nothing has focus.
+pub fn active_window() -> Option<ActiveWindow> {
+    #[cfg(target_os = "linux")]
+    {
+        match x11::active_window() {
+            Ok(window) => window,
+            Err(e) => {
+                log::debug!("Active window detection unavailable: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
@@ -0,0 +1,92 @@
+//! Matching the focused window against the user's application rules.
+
+use super::ActiveWindow;
+use crate::storage::models::AppRule;
+
+/// First enabled rule (in order) matching `window`.
+pub fn find_match<'a>(rules: &'a [AppRule], window: &ActiveWindow) -> Option<&'a AppRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .find(|rule| matches(rule, window))
+}
+
+/// Every non-empty pattern must be contained in its field, ignoring case.
+/// A rule with no patterns at all never matches.
+fn matches(rule: &AppRule, window: &ActiveWindow) -> bool {
+    if rule.window_class.is_empty() && rule.window_title.is_empty() {
+        return false;
+    }
+    contains(&window.class, &rule.window_class) && contains(&window.title, &rule.window_title)
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    needle.is_empty() || haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, window_class: &str, window_title: &str) -> AppRule {
+        AppRule {
+            id: id.to_string(),
+            window_class: window_class.to_string(),
+            window_title: window_title.to_string(),
+            profile_id: None,
+            language: None,
+            injection: None,
+            enabled: true,
+            position: 0,
+            created_at: String::new(),
+        }
+    }
+
+    fn window(class: &str, title: &str) -> ActiveWindow {
+        ActiveWindow {
+            class: class.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matches_class_and_title_ignoring_case() {
+        let rules = [
+            rule("slack", "slack", ""),
+            rule("gmail", "firefox", "Gmail"),
+        ];
+
+        let matched = find_match(&rules, &window("Slack", "general - Acme"));
+        assert_eq!(matched.map(|r| r.id.as_str()), Some("slack"));
+
+        let matched = find_match(
+            &rules,
+            &window("firefox", "Inbox (3) - gmail - Mozilla Firefox"),
+        );
+        assert_eq!(matched.map(|r| r.id.as_str()), Some("gmail"));
+
+        assert!(find_match(&rules, &window("firefox", "GitHub - Mozilla Firefox")).is_none());
+    }
+
+    #[test]
+    fn test_first_enabled_rule_wins() {
+        let mut disabled = rule("disabled", "term", "");
+        disabled.enabled = false;
+        let rules = [
+            disabled,
+            rule("vim", "", "vim"),
+            rule("terminal", "term", ""),
+        ];
+
+        let matched = find_match(&rules, &window("gnome-terminal", "nvim README.md"));
+        assert_eq!(matched.map(|r| r.id.as_str()), Some("vim"));
+        let matched = find_match(&rules, &window("xterm", "bash"));
+        assert_eq!(matched.map(|r| r.id.as_str()), Some("terminal"));
+    }
+
+    #[test]
+    fn test_empty_rule_never_matches() {
+        let rules = [rule("empty", "", "")];
+        assert!(find_match(&rules, &window("Slack", "general")).is_none());
+    }
+}
@@ -0,0 +1,81 @@
+//! Active window lookup through the X server (EWMH `_NET_ACTIVE_WINDOW`).
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::ActiveWindow;
+use crate::errors::{Result, VoiceFlowError};
+
+/// Longest property value read, in 32-bit units (titles are rarely longer than this).
+const MAX_PROPERTY_LEN: u32 = 1024;
+
+pub fn active_window() -> Result<Option<ActiveWindow>> {
+    let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+    let root = conn.setup().roots[screen].root;
+    let net_active_window = intern(&conn, b"_NET_ACTIVE_WINDOW")?;
+    let net_wm_name = intern(&conn, b"_NET_WM_NAME")?;
+    let utf8_string = intern(&conn, b"UTF8_STRING")?;
+
+    let active = conn
+        .get_property(false, root, net_active_window, AtomEnum::WINDOW, 0, 1)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    let Some(window) = active.value32().and_then(|mut v| v.next()) else {
+        return Ok(None);
+    };
+    if window == 0 {
+        return Ok(None);
+    }
+
+    // WM_CLASS holds "instance\0class\0"; the class is the human-friendly half
+    let wm_class = property(
+        &conn,
+        window,
+        AtomEnum::WM_CLASS.into(),
+        AtomEnum::STRING.into(),
+    )?;
+    let class = wm_class
+        .split(|&b| b == 0)
+        .rfind(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned())
+        .unwrap_or_default();
+
+    let mut title = property(&conn, window, net_wm_name, utf8_string)?;
+    if title.is_empty() {
+        title = property(
+            &conn,
+            window,
+            AtomEnum::WM_NAME.into(),
+            AtomEnum::STRING.into(),
+        )?;
+    }
+
+    Ok(Some(ActiveWindow {
+        class,
+        title: String::from_utf8_lossy(&title).into_owned(),
+    }))
+}
+
+fn intern(conn: &RustConnection, name: &[u8]) -> Result<Atom> {
+    let reply = conn
+        .intern_atom(false, name)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    Ok(reply.atom)
+}
+
+fn property(conn: &RustConnection, window: Window, name: Atom, kind: Atom) -> Result<Vec<u8>> {
+    let reply = conn
+        .get_property(false, window, name, kind, 0, MAX_PROPERTY_LEN)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    Ok(reply.value)
+}
+
+fn x11_error(e: impl std::fmt::Display) -> VoiceFlowError {
+    VoiceFlowError::Pipeline(format!("X11 error: {}", e))
+}
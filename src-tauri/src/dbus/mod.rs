@@ -0,0 +1,266 @@
+//! `org.voiceflow.Dictation` on the session bus, for window managers (sway, i3) that
+//! bind keys themselves; global shortcuts often fail to register under Wayland.
+//!
+//! ```text
+//! bindsym $mod+d exec busctl --user call org.voiceflow.Dictation \
+//!     /org/voiceflow/Dictation org.voiceflow.Dictation Toggle
+//! ```
+//!
+//! The methods run the same pipeline functions as the hotkeys, and `StateChanged`
+//! relays every `pipeline-state` event ("recording", "transcribing", "idle", ...).
+//! `Toggle` returns as soon as processing starts; `StopAndProcess` waits for the
+//! text, so long dictations need more than busctl's default 25 s timeout
+//! (`busctl --timeout=120 ...`).
+
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Listener};
+use zbus::connection::Builder;
+use zbus::object_server::SignalEmitter;
+use zbus::{fdo, interface, Connection};
+
+use crate::commands::pipeline::{self, PipelineResult};
+use crate::errors::{Result, VoiceFlowError};
+use crate::hotkey::HotkeyAction;
+
+pub const BUS_NAME: &str = "org.voiceflow.Dictation";
+pub const OBJECT_PATH: &str = "/org/voiceflow/Dictation";
+
+/// What the D-Bus methods drive: the app's pipeline, or a fake in tests.
+pub trait Controller: Send + Sync + 'static {
+    fn is_recording(&self) -> Result<bool>;
+
+    fn start_recording(&self) -> BoxFuture<'_, Result<()>>;
+
+    fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>>;
+
+    fn cancel(&self) -> Result<()>;
+}
+
+/// The hotkey handlers' behaviour, including showing failures in the app.
+impl Controller for AppHandle {
+    fn is_recording(&self) -> Result<bool> {
+        pipeline::is_recording(self)
+    }
+
+    fn start_recording(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let result = pipeline::begin_recording_with_overlay(self, HotkeyAction::Dictate).await;
+            if let Err(e) = &result {
+                log::error!("Recording start error: {}", e);
+                let _ = self.emit("pipeline-error", e.to_string());
+            }
+            result
+        })
+    }
+
+    fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>> {
+        Box::pin(async move {
+            let result = pipeline::process_recording(self).await;
+            match &result {
+                Ok(_) | Err(VoiceFlowError::Cancelled) => {}
+                Err(e) => {
+                    log::error!("Pipeline error: {}", e);
+                    let _ = self.emit("pipeline-error", e.to_string());
+                }
+            }
+            result
+        })
+    }
+
+    fn cancel(&self) -> Result<()> {
+        pipeline::cancel(self)
+    }
+}
+
+struct Dictation {
+    controller: Arc<dyn Controller>,
+}
+
+#[interface(name = "org.voiceflow.Dictation")]
+impl Dictation {
+    /// Start dictating, as pressing the hotkey does.
+    async fn start_recording(&self) -> fdo::Result<()> {
+        if self.controller.is_recording().map_err(failed)? {
+            return Err(fdo::Error::Failed("Already recording".into()));
+        }
+        self.controller.start_recording().await.map_err(failed)
+    }
+
+    /// Stop recording and run the pipeline. Returns the text that was typed, so the
+    /// reply takes as long as transcription and refinement.
+    async fn stop_and_process(&self) -> fdo::Result<String> {
+        if !self.controller.is_recording().map_err(failed)? {
+            return Err(fdo::Error::Failed("Not recording".into()));
+        }
+        let result = self.controller.stop_and_process().await.map_err(failed)?;
+        Ok(result.refined_text)
+    }
+
+    /// Start recording if idle, otherwise stop and process. Returns without waiting
+    /// for the result; `StateChanged` reports progress and the app shows failures.
+    async fn toggle(&self) -> fdo::Result<()> {
+        if !self.controller.is_recording().map_err(failed)? {
+            return self.start_recording().await;
+        }
+        let controller = self.controller.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = controller.stop_and_process().await;
+        });
+        Ok(())
+    }
+
+    /// Discard the recording or abort processing. No-op when idle.
+    async fn cancel(&self) -> fdo::Result<()> {
+        self.controller.cancel().map_err(failed)
+    }
+
+    #[zbus(signal)]
+    async fn state_changed(emitter: &SignalEmitter<'_>, state: &str) -> zbus::Result<()>;
+}
+
+fn failed(e: VoiceFlowError) -> fdo::Error {
+    fdo::Error::Failed(e.to_string())
+}
+
+/// Register the service on the session bus and relay pipeline states as
+/// `StateChanged`. Without a session bus (or with another instance holding the
+/// name) the app carries on without it.
+pub fn start(app: &AppHandle) {
+    let (states, mut received) = tokio::sync::mpsc::unbounded_channel::<String>();
+    app.listen_any("pipeline-state", move |event| {
+        let state = serde_json::from_str::<serde_json::Value>(event.payload())
+            .ok()
+            .and_then(|payload| Some(payload.get("state")?.as_str()?.to_string()));
+        if let Some(state) = state {
+            let _ = states.send(state);
+        }
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let served = match Builder::session() {
+            Ok(builder) => serve(builder, Arc::new(app)).await,
+            Err(e) => Err(e),
+        };
+        let connection = match served {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::warn!("D-Bus service unavailable: {}", e);
+                return;
+            }
+        };
+        log::info!("D-Bus service {} registered", BUS_NAME);
+        // Holding the connection here keeps the service alive for the app's lifetime
+        while let Some(state) = received.recv().await {
+            if let Err(e) = emit_state_changed(&connection, &state).await {
+                log::warn!("Failed to emit StateChanged: {}", e);
+            }
+        }
+    });
+}
+
+/// Claim `BUS_NAME` and serve the dictation object at `OBJECT_PATH`.
+pub async fn serve(
+    builder: Builder<'_>,
+    controller: Arc<dyn Controller>,
+) -> zbus::Result<Connection> {
+    builder
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, Dictation { controller })?
+        .build()
+        .await
+}
+
+pub async fn emit_state_changed(connection: &Connection, state: &str) -> zbus::Result<()> {
+    let emitter = SignalEmitter::new(connection, OBJECT_PATH)?;
+    Dictation::state_changed(&emitter, state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use zbus::Proxy;
+
+    #[derive(Default)]
+    struct FakeController {
+        recording: AtomicBool,
+    }
+
+    impl Controller for Arc<FakeController> {
+        fn is_recording(&self) -> Result<bool> {
+            Ok(self.recording.load(Ordering::SeqCst))
+        }
+
+        fn start_recording(&self) -> BoxFuture<'_, Result<()>> {
+            self.recording.store(true, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>> {
+            self.recording.store(false, Ordering::SeqCst);
+            Box::pin(async {
+                Ok(PipelineResult {
+                    raw_text: "hello world".into(),
+                    refined_text: "Hello, world.".into(),
+                    stt_latency_ms: 1,
+                    llm_latency_ms: 1,
+                    total_latency_ms: 2,
+                })
+            })
+        }
+
+        fn cancel(&self) -> Result<()> {
+            self.recording.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a session bus: dbus-run-session -- cargo test -- --ignored"]
+    fn test_service_on_session_bus() {
+        tauri::async_runtime::block_on(async {
+            let fake = Arc::new(FakeController::default());
+            let service = serve(Builder::session().unwrap(), Arc::new(fake.clone()))
+                .await
+                .unwrap();
+            let client = Connection::session().await.unwrap();
+            let proxy = Proxy::new(&client, BUS_NAME, OBJECT_PATH, BUS_NAME)
+                .await
+                .unwrap();
+            let mut signals = proxy.receive_signal("StateChanged").await.unwrap();
+
+            proxy.call_method("StartRecording", &()).await.unwrap();
+            assert!(fake.is_recording().unwrap());
+            assert!(proxy.call_method("StartRecording", &()).await.is_err());
+
+            let text: String = proxy.call("StopAndProcess", &()).await.unwrap();
+            assert_eq!(text, "Hello, world.");
+            assert!(proxy.call_method("StopAndProcess", &()).await.is_err());
+
+            proxy.call_method("Toggle", &()).await.unwrap();
+            assert!(fake.is_recording().unwrap());
+            proxy.call_method("Cancel", &()).await.unwrap();
+            assert!(!fake.is_recording().unwrap());
+
+            // Toggling off hands the processing to a task and replies right away
+            proxy.call_method("Toggle", &()).await.unwrap();
+            proxy.call_method("Toggle", &()).await.unwrap();
+            for _ in 0..100 {
+                if !fake.is_recording().unwrap() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            assert!(!fake.is_recording().unwrap());
+
+            emit_state_changed(&service, "recording").await.unwrap();
+            let signal = signals.next().await.unwrap();
+            let state: String = signal.body().deserialize().unwrap();
+            assert_eq!(state, "recording");
+        });
+    }
+}
@@ -5,6 +5,9 @@ pub enum VoiceFlowError {
     #[error("Audio error: {0}")]
     Audio(String),
 
+    #[error("No speech detected in the recording")]
+    NoSpeech,
+
     #[error("API error: {0}")]
     Api(String),
 
@@ -17,9 +20,15 @@ pub enum VoiceFlowError {
     #[error("Injection error: {0}")]
     Injection(String),
 
+    #[error("Hotkey error: {0}")]
+    Hotkey(String),
+
     #[error("Pipeline error: {0}")]
     Pipeline(String),
 
+    #[error("Pipeline cancelled")]
+    Cancelled,
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -1,42 +1,436 @@
-use tauri::AppHandle;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
-/// Register global hotkeys for the app.
-/// Hold-to-talk: press Ctrl+Shift+Space starts recording, release stops and processes.
+use crate::errors::VoiceFlowError;
+use crate::AppState;
+
+/// Hotkey used when no `hotkey` setting is stored or the stored one cannot be used.
+pub const DEFAULT_HOTKEY: &str = "Ctrl+Shift+Space";
+
+/// Cancel hotkey used when no `cancel_hotkey` setting is stored.
+pub const DEFAULT_CANCEL_HOTKEY: &str = "Escape";
+
+/// Presses closer together than this are ignored in toggle mode (key bounce / auto-repeat).
+const TOGGLE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Time of the last accepted toggle-mode press.
+static LAST_TOGGLE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// How the dictation hotkey drives recording, from the `hotkey_mode` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyMode {
+    /// Hold to record, release to process.
+    Hold,
+    /// First press starts recording, next press stops and processes.
+    Toggle,
+}
+
+impl HotkeyMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("toggle") => HotkeyMode::Toggle,
+            _ => HotkeyMode::Hold,
+        }
+    }
+}
+
+/// What a global hotkey starts. Each action has its own shortcut setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Dictate new text at the cursor.
+    #[default]
+    Dictate,
+    /// Speak an instruction that rewrites the selected text.
+    Command,
+    /// Dictate text that is typed in the `output_language`.
+    Translate,
+}
+
+impl HotkeyAction {
+    /// Setting holding this action's shortcut.
+    pub fn setting(self) -> &'static str {
+        match self {
+            Self::Dictate => "hotkey",
+            Self::Command => "command_hotkey",
+            Self::Translate => "translate_hotkey",
+        }
+    }
+}
+
+/// Shortcuts currently registered with the OS, so they can be unregistered later.
+#[derive(Debug, Default)]
+pub struct HotkeyBindings {
+    /// Dictation hotkey, registered for the app's whole lifetime.
+    pub dictation: Option<Shortcut>,
+    /// Command-mode hotkey; only registered once the user configures one.
+    pub command: Option<Shortcut>,
+    /// Translation hotkey; only registered once the user configures one.
+    pub translate: Option<Shortcut>,
+    /// Cancel hotkey, registered only while the overlay is visible.
+    pub cancel: Option<Shortcut>,
+}
+
+impl HotkeyBindings {
+    fn slot(&mut self, action: HotkeyAction) -> &mut Option<Shortcut> {
+        match action {
+            HotkeyAction::Dictate => &mut self.dictation,
+            HotkeyAction::Command => &mut self.command,
+            HotkeyAction::Translate => &mut self.translate,
+        }
+    }
+}
+
+/// Error returned to the frontend when a hotkey cannot be applied.
+/// Serialized as `{ "kind": "invalid" | "rejected", "hotkey": ..., "reason": ... }`.
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HotkeyError {
+    #[error("Invalid hotkey '{hotkey}': {reason}")]
+    Invalid { hotkey: String, reason: String },
+
+    #[error("Hotkey '{hotkey}' was rejected by the OS: {reason}")]
+    Rejected { hotkey: String, reason: String },
+}
+
+impl From<HotkeyError> for VoiceFlowError {
+    fn from(e: HotkeyError) -> Self {
+        VoiceFlowError::Hotkey(e.to_string())
+    }
+}
+
+/// Parse a human-readable combination like "Alt+F9" or "Super+Shift+D".
+/// Modifiers and key names are case-insensitive; exactly one non-modifier key is required.
+pub fn parse_hotkey(hotkey: &str) -> std::result::Result<Shortcut, HotkeyError> {
+    let invalid = |reason: String| HotkeyError::Invalid {
+        hotkey: hotkey.to_string(),
+        reason,
+    };
+
+    let mut mods = Modifiers::empty();
+    let mut key = None;
+
+    for raw in hotkey.split('+') {
+        let token = raw.trim();
+        if token.is_empty() {
+            return Err(invalid("empty key in combination".into()));
+        }
+
+        match token.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => mods |= Modifiers::CONTROL,
+            "shift" => mods |= Modifiers::SHIFT,
+            "alt" | "option" => mods |= Modifiers::ALT,
+            "super" | "meta" | "win" | "cmd" | "command" => mods |= Modifiers::SUPER,
+            _ => {
+                if key.is_some() {
+                    return Err(invalid("only one non-modifier key is allowed".into()));
+                }
+                let code =
+                    parse_key(token).ok_or_else(|| invalid(format!("unknown key '{}'", token)))?;
+                key = Some(code);
+            }
+        }
+    }
+
+    let key = key.ok_or_else(|| invalid("a non-modifier key is required".into()))?;
+    let mods = if mods.is_empty() { None } else { Some(mods) };
+    Ok(Shortcut::new(mods, key))
+}
+
+/// Map a single key token to a `Code`: letters, digits, common aliases and W3C code names.
+fn parse_key(token: &str) -> Option<Code> {
+    let mut chars = token.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphabetic() {
+            return format!("Key{}", c.to_ascii_uppercase()).parse().ok();
+        }
+        if c.is_ascii_digit() {
+            return format!("Digit{}", c).parse().ok();
+        }
+    }
+
+    let lower = token.to_ascii_lowercase();
+    let alias = match lower.as_str() {
+        "esc" => Some("Escape"),
+        "return" => Some("Enter"),
+        "del" => Some("Delete"),
+        "ins" => Some("Insert"),
+        "pageup" | "pgup" => Some("PageUp"),
+        "pagedown" | "pgdn" => Some("PageDown"),
+        "up" | "arrowup" => Some("ArrowUp"),
+        "down" | "arrowdown" => Some("ArrowDown"),
+        "left" | "arrowleft" => Some("ArrowLeft"),
+        "right" | "arrowright" => Some("ArrowRight"),
+        "printscreen" | "prtsc" => Some("PrintScreen"),
+        "capslock" => Some("CapsLock"),
+        "scrolllock" => Some("ScrollLock"),
+        "`" | "backquote" => Some("Backquote"),
+        "-" | "minus" => Some("Minus"),
+        "=" | "equal" => Some("Equal"),
+        "[" | "bracketleft" => Some("BracketLeft"),
+        "]" | "bracketright" => Some("BracketRight"),
+        ";" | "semicolon" => Some("Semicolon"),
+        "'" | "quote" => Some("Quote"),
+        "," | "comma" => Some("Comma"),
+        "." | "period" => Some("Period"),
+        "/" | "slash" => Some("Slash"),
+        "\\" | "backslash" => Some("Backslash"),
+        _ => None,
+    };
+    if let Some(name) = alias {
+        return name.parse().ok();
+    }
+
+    // Function keys ("f9") and single-word names ("space", "TAB")
+    if let Some(n) = lower.strip_prefix('f') {
+        if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) {
+            return format!("F{}", n).parse().ok();
+        }
+    }
+    let mut capitalized = lower;
+    if let Some(first) = capitalized.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    capitalized.parse().ok().or_else(|| token.parse().ok())
+}
+
+/// Register global hotkeys for the app from the stored `hotkey`, `command_hotkey` and
+/// `translate_hotkey` settings.
+/// The `hotkey_mode` setting is read on every event, so switching modes needs no re-registration.
+/// Falls back to `DEFAULT_HOTKEY` if the stored one is invalid or refused by the OS.
 pub fn register_hotkeys(app: &AppHandle) -> crate::errors::Result<()> {
-    use tauri_plugin_global_shortcut::{
-        Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState,
+    let setting = |action: HotkeyAction| -> crate::errors::Result<Option<String>> {
+        let state = app.state::<AppState>();
+        let db = state
+            .db
+            .lock()
+            .map_err(|e| VoiceFlowError::Hotkey(format!("DB lock poisoned: {}", e)))?;
+        Ok(db.get_setting(action.setting()).ok().flatten())
+    };
+    let saved = setting(HotkeyAction::Dictate)?;
+
+    // The other hotkeys are optional, so a bad one is only logged
+    for action in [HotkeyAction::Command, HotkeyAction::Translate] {
+        let Some(hotkey) = setting(action)?.filter(|s| !s.trim().is_empty()) else {
+            continue;
+        };
+        if let Err(e) = parse_hotkey(&hotkey).and_then(|s| bind_hotkey(app, s, &hotkey, action)) {
+            log::warn!("{:?} hotkey unavailable: {}", action, e);
+        }
+    }
+
+    if let Some(saved) = saved.filter(|s| !s.trim().is_empty()) {
+        match parse_hotkey(&saved).and_then(|s| bind_hotkey(app, s, &saved, HotkeyAction::Dictate))
+        {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!("{} — falling back to {}", e, DEFAULT_HOTKEY),
+        }
+    }
+
+    let shortcut = parse_hotkey(DEFAULT_HOTKEY)?;
+    bind_hotkey(app, shortcut, DEFAULT_HOTKEY, HotkeyAction::Dictate)?;
+    Ok(())
+}
+
+/// Replace the hotkey for `action` with `hotkey` without restarting the app.
+/// The previous hotkey is restored if the OS refuses the new combination.
+/// An empty `hotkey` turns an optional hotkey off; dictation always needs one.
+pub fn rebind_hotkey(
+    app: &AppHandle,
+    hotkey: &str,
+    action: HotkeyAction,
+) -> std::result::Result<(), HotkeyError> {
+    let shortcut = if hotkey.is_empty() && action != HotkeyAction::Dictate {
+        None
+    } else {
+        Some(parse_hotkey(hotkey)?)
     };
+    let previous = active_hotkey(app, action);
+
+    if previous == shortcut {
+        return Ok(());
+    }
 
-    let shortcut = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space);
+    if let Some(prev) = previous {
+        if let Err(e) = app.global_shortcut().unregister(prev) {
+            log::warn!("Failed to unregister previous hotkey: {}", e);
+        }
+        set_active_hotkey(app, action, None);
+    }
 
+    let Some(shortcut) = shortcut else {
+        log::info!("{:?} hotkey disabled", action);
+        return Ok(());
+    };
+
+    if let Err(e) = bind_hotkey(app, shortcut, hotkey, action) {
+        if let Some(prev) = previous {
+            if let Err(restore_err) = bind_hotkey(app, prev, &prev.to_string(), action) {
+                log::error!("Failed to restore previous hotkey: {}", restore_err);
+            }
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Register `shortcut` as the hotkey for `action` and remember it as the active one.
+fn bind_hotkey(
+    app: &AppHandle,
+    shortcut: Shortcut,
+    label: &str,
+    action: HotkeyAction,
+) -> std::result::Result<(), HotkeyError> {
     app.global_shortcut()
         .on_shortcut(shortcut, move |app, _shortcut, event| {
             let app = app.clone();
-            match event.state {
-                ShortcutState::Pressed => {
+            match (hotkey_mode(&app), event.state) {
+                (HotkeyMode::Hold, ShortcutState::Pressed) => {
                     log::info!("Hotkey pressed - starting recording");
                     tauri::async_runtime::spawn(async move {
-                        on_hotkey_press(&app).await;
+                        on_hotkey_press(&app, action).await;
                     });
                 }
-                ShortcutState::Released => {
+                (HotkeyMode::Hold, ShortcutState::Released) => {
                     log::info!("Hotkey released - stopping and processing");
                     tauri::async_runtime::spawn(async move {
                         on_hotkey_release(&app).await;
                     });
                 }
+                (HotkeyMode::Toggle, ShortcutState::Pressed) => {
+                    if !accept_toggle_press() {
+                        return;
+                    }
+                    log::info!("Hotkey pressed - toggling recording");
+                    tauri::async_runtime::spawn(async move {
+                        on_hotkey_toggle(&app, action).await;
+                    });
+                }
+                (HotkeyMode::Toggle, ShortcutState::Released) => {}
             }
         })
-        .map_err(|e| crate::errors::VoiceFlowError::Pipeline(e.to_string()))?;
+        .map_err(|e| HotkeyError::Rejected {
+            hotkey: label.to_string(),
+            reason: e.to_string(),
+        })?;
 
-    log::info!("Global hotkey registered: Ctrl+Shift+Space (hold-to-talk)");
+    set_active_hotkey(app, action, Some(shortcut));
+    log::info!("Global {:?} hotkey registered: {}", action, label);
     Ok(())
 }
 
-/// On hotkey press: start recording if not already recording.
-async fn on_hotkey_press(app: &AppHandle) {
-    use tauri::Manager;
+fn hotkey_mode(app: &AppHandle) -> HotkeyMode {
+    let state = app.state::<AppState>();
+    let mode = match state.db.lock() {
+        Ok(db) => db.get_setting("hotkey_mode").ok().flatten(),
+        Err(_) => None,
+    };
+    HotkeyMode::from_setting(mode.as_deref())
+}
+
+/// Debounce toggle-mode presses; returns false if this press came too soon after the last one.
+fn accept_toggle_press() -> bool {
+    let Ok(mut last) = LAST_TOGGLE.lock() else {
+        return true;
+    };
+    let now = Instant::now();
+    if last.is_some_and(|t| now.duration_since(t) < TOGGLE_DEBOUNCE) {
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+fn active_hotkey(app: &AppHandle, action: HotkeyAction) -> Option<Shortcut> {
+    let state = app.state::<AppState>();
+    let active = state.hotkeys.lock().ok().and_then(|mut h| *h.slot(action));
+    active
+}
+
+fn set_active_hotkey(app: &AppHandle, action: HotkeyAction, shortcut: Option<Shortcut>) {
+    let state = app.state::<AppState>();
+    if let Ok(mut bindings) = state.hotkeys.lock() {
+        *bindings.slot(action) = shortcut;
+    }
+}
+
+/// Register the cancel hotkey (`cancel_hotkey` setting, Escape by default).
+/// Called when the overlay appears so the key is only grabbed while dictation is active.
+pub fn bind_cancel_hotkey(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let configured = match state.db.lock() {
+        Ok(db) => db.get_setting("cancel_hotkey").ok().flatten(),
+        Err(_) => None,
+    };
+    let label = configured
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CANCEL_HOTKEY.to_string());
+
+    let shortcut = match parse_hotkey(&label) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    };
+
+    {
+        let Ok(bindings) = state.hotkeys.lock() else {
+            return;
+        };
+        if bindings.cancel.is_some()
+            || bindings.dictation == Some(shortcut)
+            || bindings.command == Some(shortcut)
+            || bindings.translate == Some(shortcut)
+        {
+            return;
+        }
+    }
+
+    let result = app
+        .global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                log::info!("Cancel hotkey pressed");
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::commands::pipeline::cancel(&app) {
+                        log::error!("Cancel error: {}", e);
+                    }
+                });
+            }
+        });
 
+    match result {
+        Ok(()) => {
+            if let Ok(mut bindings) = state.hotkeys.lock() {
+                bindings.cancel = Some(shortcut);
+            }
+        }
+        Err(e) => log::warn!("Failed to register cancel hotkey {}: {}", label, e),
+    }
+}
+
+/// Release the cancel hotkey so the key goes back to the focused app.
+pub fn unbind_cancel_hotkey(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let shortcut = match state.hotkeys.lock() {
+        Ok(mut bindings) => bindings.cancel.take(),
+        Err(_) => None,
+    };
+    if let Some(shortcut) = shortcut {
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            log::warn!("Failed to unregister cancel hotkey: {}", e);
+        }
+    }
+}
+
+/// On hotkey press: start recording for `action` if not already recording.
+async fn on_hotkey_press(app: &AppHandle, action: HotkeyAction) {
     let is_recording = {
         let state = app.state::<crate::AppState>();
         let lock_result = state.audio.lock();
@@ -47,16 +441,11 @@ async fn on_hotkey_press(app: &AppHandle) {
     };
 
     if !is_recording {
-        match crate::commands::pipeline::start_recording(app.clone()).await {
-            Ok(_) => {
-                log::info!("Recording started via hotkey");
-                // Show overlay and start emitting audio levels
-                crate::commands::overlay::show_overlay(app);
-                crate::commands::overlay::start_audio_level_emitter(app.clone());
-            }
+        match crate::commands::pipeline::begin_recording_with_overlay(app, action).await {
+            Ok(_) => log::info!("Recording started via {:?} hotkey", action),
             Err(e) => {
                 log::error!("Recording start error: {}", e);
-                let _ = tauri::Emitter::emit(app, "pipeline-error", e);
+                let _ = tauri::Emitter::emit(app, "pipeline-error", e.to_string());
             }
         }
     }
@@ -64,8 +453,6 @@ async fn on_hotkey_press(app: &AppHandle) {
 
 /// On hotkey release: stop recording and process the pipeline.
 async fn on_hotkey_release(app: &AppHandle) {
-    use tauri::Manager;
-
     let is_recording = {
         let state = app.state::<crate::AppState>();
         let lock_result = state.audio.lock();
@@ -76,12 +463,107 @@ async fn on_hotkey_release(app: &AppHandle) {
     };
 
     if is_recording {
-        match crate::commands::pipeline::stop_and_process(app.clone()).await {
+        match crate::commands::pipeline::process_recording(app).await {
             Ok(_) => log::info!("Pipeline completed via hotkey release"),
+            Err(VoiceFlowError::Cancelled) => {}
             Err(e) => {
                 log::error!("Pipeline error: {}", e);
-                let _ = tauri::Emitter::emit(app, "pipeline-error", e);
+                let _ = tauri::Emitter::emit(app, "pipeline-error", e.to_string());
             }
         }
     }
 }
+
+/// On hotkey press in toggle mode: start recording if idle, otherwise stop and process.
+async fn on_hotkey_toggle(app: &AppHandle, action: HotkeyAction) {
+    let is_recording = {
+        let state = app.state::<crate::AppState>();
+        let lock_result = state.audio.lock();
+        match lock_result {
+            Ok(audio) => audio.is_recording(),
+            Err(_) => return,
+        }
+    };
+
+    if is_recording {
+        on_hotkey_release(app).await;
+    } else {
+        on_hotkey_press(app, action).await;
+    }
+}
+
+// Tauri commands for hotkey configuration
+#[tauri::command]
+pub async fn set_hotkey(
+    app: AppHandle,
+    hotkey: String,
+    action: Option<HotkeyAction>,
+) -> std::result::Result<(), HotkeyError> {
+    let action = action.unwrap_or_default();
+    let hotkey = hotkey.trim().to_string();
+    rebind_hotkey(&app, &hotkey, action)?;
+
+    let state = app.state::<AppState>();
+    let saved = state.db.lock().map_err(|e| e.to_string()).and_then(|db| {
+        db.set_setting(action.setting(), &hotkey)
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = saved {
+        log::error!("Failed to save hotkey setting: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_hotkey() {
+        let shortcut = parse_hotkey(DEFAULT_HOTKEY).unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space)
+        );
+    }
+
+    #[test]
+    fn test_parse_hotkey_variants() {
+        assert_eq!(
+            parse_hotkey("Alt+F9").unwrap(),
+            Shortcut::new(Some(Modifiers::ALT), Code::F9)
+        );
+        assert_eq!(
+            parse_hotkey("super + shift + d").unwrap(),
+            Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyD)
+        );
+        assert_eq!(
+            parse_hotkey("Ctrl+Alt+PgUp").unwrap(),
+            Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::PageUp)
+        );
+        assert_eq!(parse_hotkey("F13").unwrap(), Shortcut::new(None, Code::F13));
+        assert_eq!(
+            parse_hotkey("Win+1").unwrap(),
+            Shortcut::new(Some(Modifiers::SUPER), Code::Digit1)
+        );
+    }
+
+    #[test]
+    fn test_hotkey_mode_from_setting() {
+        assert_eq!(HotkeyMode::from_setting(Some("toggle")), HotkeyMode::Toggle);
+        assert_eq!(HotkeyMode::from_setting(Some("hold")), HotkeyMode::Hold);
+        assert_eq!(HotkeyMode::from_setting(Some("bogus")), HotkeyMode::Hold);
+        assert_eq!(HotkeyMode::from_setting(None), HotkeyMode::Hold);
+    }
+
+    #[test]
+    fn test_parse_hotkey_rejects_malformed() {
+        for bad in ["", "Ctrl+", "Ctrl+Shift", "Ctrl+A+B", "Ctrl+Banana"] {
+            assert!(
+                matches!(parse_hotkey(bad), Err(HotkeyError::Invalid { .. })),
+                "expected '{}' to be rejected",
+                bad
+            );
+        }
+    }
+}
@@ -2,39 +2,84 @@ use crate::errors::Result;
 use keyring::Entry;
 
 const SERVICE_NAME: &str = "voice-flow";
+/// Provider whose key is used when none is specified (Groq also powers refinement).
+pub const DEFAULT_PROVIDER: &str = "groq";
 
-pub fn set_api_key(key: &str) -> Result<()> {
-    let entry = Entry::new(SERVICE_NAME, "groq_api_key")?;
-    entry.set_password(key)?;
-    log::info!("API key stored in keychain");
+/// Keychain entry for a provider's key, e.g. "groq_api_key" or "deepgram_api_key".
+fn entry(provider: &str) -> Result<Entry> {
+    Ok(Entry::new(SERVICE_NAME, &format!("{}_api_key", provider))?)
+}
+
+pub fn set_provider_key(provider: &str, key: &str) -> Result<()> {
+    entry(provider)?.set_password(key)?;
+    log::info!("{} API key stored in keychain", provider);
     Ok(())
 }
 
-pub fn get_api_key() -> Result<String> {
-    let entry = Entry::new(SERVICE_NAME, "groq_api_key")?;
-    let key = entry.get_password()?;
+pub fn get_provider_key(provider: &str) -> Result<String> {
+    let key = entry(provider)?.get_password()?;
     Ok(key)
 }
 
-pub fn delete_api_key() -> Result<()> {
-    let entry = Entry::new(SERVICE_NAME, "groq_api_key")?;
-    entry.delete_credential()?;
-    log::info!("API key removed from keychain");
+/// The key to call a provider with: the keychain entry, or the provider's
+/// environment variable (see `env_var`) when the keychain has none or is
+/// unavailable, as on headless machines.
+pub fn resolve_provider_key(provider: &str) -> Result<String> {
+    get_provider_key(provider).or_else(|e| match std::env::var(env_var(provider)) {
+        Ok(key) if !key.is_empty() => Ok(key),
+        _ => Err(e),
+    })
+}
+
+/// Environment variable holding a provider's key, e.g. `GROQ_API_KEY`.
+fn env_var(provider: &str) -> String {
+    format!("{}_API_KEY", provider.to_uppercase())
+}
+
+pub fn delete_provider_key(provider: &str) -> Result<()> {
+    entry(provider)?.delete_credential()?;
+    log::info!("{} API key removed from keychain", provider);
     Ok(())
 }
 
-// Tauri commands for keychain access
+pub fn set_api_key(key: &str) -> Result<()> {
+    set_provider_key(DEFAULT_PROVIDER, key)
+}
+
+pub fn get_api_key() -> Result<String> {
+    get_provider_key(DEFAULT_PROVIDER)
+}
+
+pub fn delete_api_key() -> Result<()> {
+    delete_provider_key(DEFAULT_PROVIDER)
+}
+
+// Tauri commands for keychain access; `provider` defaults to Groq
 #[tauri::command]
-pub fn save_api_key(key: String) -> std::result::Result<(), String> {
-    set_api_key(&key).map_err(|e| e.to_string())
+pub fn save_api_key(key: String, provider: Option<String>) -> std::result::Result<(), String> {
+    let provider = provider.as_deref().unwrap_or(DEFAULT_PROVIDER);
+    set_provider_key(provider, &key).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn load_api_key() -> std::result::Result<String, String> {
-    get_api_key().map_err(|e| e.to_string())
+pub fn load_api_key(provider: Option<String>) -> std::result::Result<String, String> {
+    let provider = provider.as_deref().unwrap_or(DEFAULT_PROVIDER);
+    get_provider_key(provider).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn remove_api_key() -> std::result::Result<(), String> {
-    delete_api_key().map_err(|e| e.to_string())
+pub fn remove_api_key(provider: Option<String>) -> std::result::Result<(), String> {
+    let provider = provider.as_deref().unwrap_or(DEFAULT_PROVIDER);
+    delete_provider_key(provider).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var() {
+        assert_eq!(env_var("groq"), "GROQ_API_KEY");
+        assert_eq!(env_var("openai_refine"), "OPENAI_REFINE_API_KEY");
+    }
 }
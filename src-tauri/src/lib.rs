@@ -1,13 +1,23 @@
 mod api;
 mod audio;
+pub mod cli;
 mod commands;
+mod context;
+#[cfg(target_os = "linux")]
+mod dbus;
 mod errors;
 mod hotkey;
 pub mod keychain;
+mod server;
 mod storage;
+mod text;
 mod tray;
 
 use audio::capture::AudioState;
+use commands::pipeline::{PipelineCancel, RecordingMode};
+use hotkey::HotkeyBindings;
+use server::ApiServer;
+use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 use storage::database::Database;
 use tauri::{Manager, WindowEvent};
@@ -15,6 +25,16 @@ use tauri::{Manager, WindowEvent};
 pub struct AppState {
     pub audio: Mutex<AudioState>,
     pub db: Mutex<Database>,
+    pub hotkeys: Mutex<HotkeyBindings>,
+    pub cancel: PipelineCancel,
+    /// Set while an audio file is transcribed; recordings cannot start meanwhile.
+    pub file_job: AtomicBool,
+    /// Window focused when the current recording started (see `context`).
+    pub focus: Mutex<Option<context::ActiveWindow>>,
+    /// What the current recording is for (dictation or command mode).
+    pub mode: Mutex<RecordingMode>,
+    /// The local control API, while it is running (see `server`).
+    pub api_server: Mutex<Option<ApiServer>>,
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -65,6 +85,12 @@ pub fn run() {
             app.manage(AppState {
                 audio: Mutex::new(AudioState::new()),
                 db: Mutex::new(db),
+                hotkeys: Mutex::new(HotkeyBindings::default()),
+                cancel: PipelineCancel::default(),
+                file_job: AtomicBool::new(false),
+                focus: Mutex::new(None),
+                mode: Mutex::new(RecordingMode::default()),
+                api_server: Mutex::new(None),
             });
 
             // Create system tray (graceful fallback if tray unavailable)
@@ -79,6 +105,13 @@ pub fn run() {
                     log::error!("Failed to register hotkeys: {}", e);
                 });
 
+            // Start the local control API if enabled
+            server::start_from_settings(app.handle());
+
+            // Window managers bind keys through D-Bus where global shortcuts fail
+            #[cfg(target_os = "linux")]
+            dbus::start(app.handle());
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -90,16 +123,43 @@ pub fn run() {
             }
         })
         .invoke_handler(tauri::generate_handler![
+            commands::audio::list_input_devices,
             commands::pipeline::start_recording,
             commands::pipeline::stop_and_process,
+            commands::pipeline::cancel_pipeline,
+            commands::pipeline::transcribe_file,
             commands::storage::get_transcriptions,
             commands::storage::delete_transcription,
+            commands::storage::get_transcription_segments,
             commands::storage::get_stats,
             commands::storage::get_setting,
             commands::storage::set_setting,
+            commands::storage::get_vocabulary,
+            commands::storage::add_vocabulary_term,
+            commands::storage::delete_vocabulary_term,
+            commands::storage::get_replacement_rules,
+            commands::storage::add_replacement_rule,
+            commands::storage::update_replacement_rule,
+            commands::storage::delete_replacement_rule,
+            commands::storage::reorder_replacement_rules,
+            commands::storage::get_prompt_profiles,
+            commands::storage::add_prompt_profile,
+            commands::storage::update_prompt_profile,
+            commands::storage::delete_prompt_profile,
+            commands::storage::get_app_rules,
+            commands::storage::add_app_rule,
+            commands::storage::update_app_rule,
+            commands::storage::delete_app_rule,
+            commands::storage::reorder_app_rules,
+            commands::storage::get_active_window,
+            commands::subtitles::export_subtitles,
+            hotkey::set_hotkey,
             keychain::save_api_key,
             keychain::load_api_key,
             keychain::remove_api_key,
+            server::get_api_server,
+            server::set_api_server,
+            server::regenerate_api_token,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
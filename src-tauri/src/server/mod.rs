@@ -0,0 +1,630 @@
+//! Opt-in control API for editors and automation (Neovim, VS Code tasks, Stream Deck).
+//! Listens on 127.0.0.1 only and requires the token from Settings, sent as
+//! `Authorization: Bearer <token>` or, for WebSocket clients that cannot set
+//! headers, `?token=<token>`.
+//!
+//! - `POST /recording/start[?action=dictate|command|translate]`
+//! - `POST /recording/stop` — processes the recording and returns the result
+//! - `POST /recording/cancel`
+//! - `GET /history[?limit=&offset=&search=]`, `GET /stats`
+//! - `GET /events` — WebSocket relaying the app's pipeline events as
+//!   `{"event": "pipeline-state", "payload": {...}}`
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, MutexGuard, RwLock};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, EventId, Listener, Manager};
+use tokio::sync::{broadcast, watch};
+
+use crate::commands::pipeline::{self, PipelineResult};
+use crate::errors::{Result, VoiceFlowError};
+use crate::hotkey::HotkeyAction;
+use crate::storage::models::{Transcription, TranscriptionStats};
+use crate::AppState;
+
+/// Port used when no `api_port` setting is stored.
+pub const DEFAULT_PORT: u16 = 7865;
+
+/// Events relayed to `/events` subscribers, as emitted to the webview.
+const FORWARDED_EVENTS: [&str; 4] = [
+    "pipeline-state",
+    "audio-level",
+    "pipeline-complete",
+    "pipeline-error",
+];
+
+/// Messages a slow WebSocket client may fall behind by before it skips ahead.
+/// Audio levels alone arrive 20 times a second.
+const EVENT_BUFFER: usize = 64;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 20;
+
+/// A running server.
+pub struct ApiServer {
+    port: u16,
+    task: JoinHandle<()>,
+    listeners: Vec<EventId>,
+    /// Shared with the request handlers, so a new token applies without a restart.
+    token: Arc<RwLock<String>>,
+    /// Set on stop. Connections are served by their own tasks and outlive the
+    /// listener, so handlers check this too.
+    stopped: watch::Sender<bool>,
+}
+
+impl ApiServer {
+    /// Stop accepting connections and relaying events. The port is free once this
+    /// returns. Idle connections and WebSockets are closed; requests already being
+    /// handled finish, and any later request on a lingering connection gets a 503.
+    async fn stop(self, app: &AppHandle) {
+        for id in self.listeners {
+            app.unlisten(id);
+        }
+        self.stopped.send_replace(true);
+        // Graceful shutdown would wait for in-flight requests (a whole dictation
+        // for `/recording/stop`); aborting drops the listener right away
+        self.task.abort();
+        let _ = self.task.await;
+        log::info!("Local API stopped on port {}", self.port);
+    }
+}
+
+/// Server settings and state, as shown in Settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+/// What the endpoints drive: the app, or a fake in tests.
+trait Backend: Send + Sync + 'static {
+    fn is_recording(&self) -> Result<bool>;
+
+    fn start_recording(&self, action: HotkeyAction) -> BoxFuture<'_, Result<()>>;
+
+    fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>>;
+
+    fn cancel(&self) -> Result<()>;
+
+    fn history(&self, limit: i64, offset: i64, search: Option<&str>) -> Result<Vec<Transcription>>;
+
+    fn stats(&self) -> Result<TranscriptionStats>;
+
+    /// Show a failed pipeline call in the app, as a failed hotkey press would.
+    fn report_error(&self, error: &VoiceFlowError);
+}
+
+impl Backend for AppHandle {
+    fn is_recording(&self) -> Result<bool> {
+        pipeline::is_recording(self)
+    }
+
+    fn start_recording(&self, action: HotkeyAction) -> BoxFuture<'_, Result<()>> {
+        Box::pin(pipeline::begin_recording_with_overlay(self, action))
+    }
+
+    fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>> {
+        Box::pin(pipeline::process_recording(self))
+    }
+
+    fn cancel(&self) -> Result<()> {
+        pipeline::cancel(self)
+    }
+
+    fn history(&self, limit: i64, offset: i64, search: Option<&str>) -> Result<Vec<Transcription>> {
+        let state = self.state::<AppState>();
+        let db = state
+            .db
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
+        db.get_transcriptions(limit, offset, search)
+    }
+
+    fn stats(&self) -> Result<TranscriptionStats> {
+        let state = self.state::<AppState>();
+        let db = state
+            .db
+            .lock()
+            .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
+        db.get_stats()
+    }
+
+    fn report_error(&self, error: &VoiceFlowError) {
+        let _ = self.emit("pipeline-error", error.to_string());
+    }
+}
+
+/// Shared by the request handlers.
+#[derive(Clone)]
+struct Api {
+    backend: Arc<dyn Backend>,
+    token: Arc<RwLock<String>>,
+    stopped: watch::Receiver<bool>,
+    events: broadcast::Sender<String>,
+}
+
+/// Start the server at launch if the `api_server` setting is on.
+pub fn start_from_settings(app: &AppHandle) {
+    match status(app) {
+        Ok(status) if status.enabled => match start(app, status.port, &status.token) {
+            Ok(server) => {
+                if let Ok(mut slot) = server_slot(app) {
+                    *slot = Some(server);
+                }
+            }
+            Err(e) => log::error!("Failed to start local API: {}", e),
+        },
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to read local API settings: {}", e),
+    }
+}
+
+/// Read the settings, creating the token on first use.
+fn status(app: &AppHandle) -> Result<ApiServerStatus> {
+    let running = server_slot(app)?.is_some();
+    let state = app.state::<AppState>();
+    let db = state
+        .db
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("DB lock poisoned: {}", e)))?;
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+    let token = match setting("api_token").filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => {
+            let token = new_token();
+            db.set_setting("api_token", &token)?;
+            token
+        }
+    };
+    Ok(ApiServerStatus {
+        enabled: setting("api_server").as_deref() == Some("true"),
+        running,
+        port: setting("api_port")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(DEFAULT_PORT),
+        token,
+    })
+}
+
+fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn server_slot(app: &AppHandle) -> Result<MutexGuard<'_, Option<ApiServer>>> {
+    app.state::<AppState>()
+        .inner()
+        .api_server
+        .lock()
+        .map_err(|e| VoiceFlowError::Pipeline(format!("API server lock poisoned: {}", e)))
+}
+
+/// Stop any running server and, if enabled, start one with the current settings.
+/// The port is bound before returning, so a port in use is reported here.
+async fn restart(app: &AppHandle, status: &ApiServerStatus) -> Result<()> {
+    let running = server_slot(app)?.take();
+    if let Some(server) = running {
+        server.stop(app).await;
+    }
+    if status.enabled {
+        let server = start(app, status.port, &status.token)?;
+        *server_slot(app)? = Some(server);
+    }
+    Ok(())
+}
+
+fn start(app: &AppHandle, port: u16, token: &str) -> Result<ApiServer> {
+    let listener = std::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))?;
+    listener.set_nonblocking(true)?;
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let listeners = FORWARDED_EVENTS
+        .iter()
+        .map(|&name| {
+            let events = events.clone();
+            app.listen_any(name, move |event| {
+                let payload =
+                    serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                let message = serde_json::json!({ "event": name, "payload": payload });
+                // Nobody may be subscribed; that is fine
+                let _ = events.send(message.to_string());
+            })
+        })
+        .collect();
+
+    let token = Arc::new(RwLock::new(token.to_string()));
+    let (stopped, stopped_rx) = watch::channel(false);
+    let router = router(Api {
+        backend: Arc::new(app.clone()),
+        token: token.clone(),
+        stopped: stopped_rx.clone(),
+        events,
+    });
+    let task = tauri::async_runtime::spawn(async move {
+        let served = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => {
+                axum::serve(listener, router)
+                    .with_graceful_shutdown(until_stopped(stopped_rx))
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = served {
+            log::error!("Local API failed: {}", e);
+        }
+    });
+    log::info!("Local API listening on 127.0.0.1:{}", port);
+
+    Ok(ApiServer {
+        port,
+        task,
+        listeners,
+        token,
+        stopped,
+    })
+}
+
+/// Resolves once the server is stopped.
+async fn until_stopped(mut stopped: watch::Receiver<bool>) {
+    let _ = stopped.wait_for(|stopped| *stopped).await;
+}
+
+fn router(api: Api) -> Router {
+    Router::new()
+        .route("/recording/start", post(start_recording))
+        .route("/recording/stop", post(stop_recording))
+        .route("/recording/cancel", post(cancel_recording))
+        .route("/history", get(history))
+        .route("/stats", get(stats))
+        .route("/events", get(events))
+        .route_layer(middleware::from_fn_with_state(api.clone(), authorize))
+        .with_state(api)
+}
+
+async fn authorize(State(api): State<Api>, request: Request, next: Next) -> Response {
+    if *api.stopped.borrow() {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The local API is turned off",
+        );
+    }
+    let authorized = match api.token.read() {
+        Ok(token) => is_authorized(request.headers(), request.uri().query(), &token),
+        Err(_) => false,
+    };
+    if authorized {
+        next.run(request).await
+    } else {
+        error_response(StatusCode::UNAUTHORIZED, "Missing or invalid token")
+    }
+}
+
+/// The token from the `Authorization: Bearer` header or the `token` query parameter.
+fn is_authorized(headers: &HeaderMap, query: Option<&str>, token: &str) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let param = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("token="));
+    [bearer, param]
+        .into_iter()
+        .flatten()
+        .any(|candidate| constant_time_eq(candidate.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early, so response times do not reveal the token.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[derive(Deserialize)]
+struct StartParams {
+    #[serde(default)]
+    action: HotkeyAction,
+}
+
+async fn start_recording(State(api): State<Api>, Query(params): Query<StartParams>) -> Response {
+    match api.backend.is_recording() {
+        Ok(true) => return error_response(StatusCode::CONFLICT, "Already recording"),
+        Ok(false) => {}
+        Err(e) => return failure(&api, e),
+    }
+    match api.backend.start_recording(params.action).await {
+        Ok(()) => {
+            log::info!("Recording started via local API ({:?})", params.action);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => failure(&api, e),
+    }
+}
+
+async fn stop_recording(State(api): State<Api>) -> Response {
+    match api.backend.is_recording() {
+        Ok(true) => {}
+        Ok(false) => return error_response(StatusCode::CONFLICT, "Not recording"),
+        Err(e) => return failure(&api, e),
+    }
+    match api.backend.stop_and_process().await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => failure(&api, e),
+    }
+}
+
+async fn cancel_recording(State(api): State<Api>) -> Response {
+    match api.backend.cancel() {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => failure(&api, e),
+    }
+}
+
+#[derive(Deserialize)]
+struct HistoryParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    search: Option<String>,
+}
+
+async fn history(State(api): State<Api>, Query(params): Query<HistoryParams>) -> Response {
+    let result = api.backend.history(
+        params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+        params.offset.unwrap_or(0),
+        params.search.as_deref().filter(|s| !s.is_empty()),
+    );
+    match result {
+        Ok(transcriptions) => Json(transcriptions).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+async fn stats(State(api): State<Api>) -> Response {
+    match api.backend.stats() {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+async fn events(State(api): State<Api>, upgrade: WebSocketUpgrade) -> Response {
+    let receiver = api.events.subscribe();
+    upgrade.on_upgrade(move |socket| relay_events(socket, receiver, api.stopped))
+}
+
+/// Send every pipeline event to the client until it disconnects or the server stops.
+async fn relay_events(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<String>,
+    stopped: watch::Receiver<bool>,
+) {
+    let mut stopped = std::pin::pin!(until_stopped(stopped));
+    loop {
+        tokio::select! {
+            _ = &mut stopped => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            event = events.recv() => match event {
+                Ok(message) => {
+                    if socket.send(Message::Text(message.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Local API client fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // Clients only listen; anything they send is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Report a failed pipeline call to the client, and to the app like a hotkey would.
+fn failure(api: &Api, error: VoiceFlowError) -> Response {
+    let status = match error {
+        VoiceFlowError::Cancelled => StatusCode::CONFLICT,
+        VoiceFlowError::NoSpeech => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => {
+            log::error!("Local API request failed: {}", error);
+            api.backend.report_error(&error);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    error_response(status, &error.to_string())
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[tauri::command]
+pub fn get_api_server(app: AppHandle) -> std::result::Result<ApiServerStatus, String> {
+    status(&app).map_err(|e| e.to_string())
+}
+
+/// Turn the server on or off and change its port, restarting it as needed.
+#[tauri::command]
+pub async fn set_api_server(
+    app: AppHandle,
+    enabled: bool,
+    port: u16,
+) -> std::result::Result<ApiServerStatus, String> {
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".into());
+    }
+    {
+        let state = app.state::<AppState>();
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.set_setting("api_server", if enabled { "true" } else { "false" })
+            .map_err(|e| e.to_string())?;
+        db.set_setting("api_port", &port.to_string())
+            .map_err(|e| e.to_string())?;
+    }
+    let settings = status(&app).map_err(|e| e.to_string())?;
+    restart(&app, &settings).await.map_err(|e| e.to_string())?;
+    status(&app).map_err(|e| e.to_string())
+}
+
+/// Replace the token, invalidating the old one right away.
+#[tauri::command]
+pub fn regenerate_api_token(app: AppHandle) -> std::result::Result<ApiServerStatus, String> {
+    let token = new_token();
+    {
+        let state = app.state::<AppState>();
+        let db = state.db.lock().map_err(|e| e.to_string())?;
+        db.set_setting("api_token", &token)
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(server) = server_slot(&app).map_err(|e| e.to_string())?.as_ref() {
+        if let Ok(mut current) = server.token.write() {
+            *current = token;
+        }
+    }
+    status(&app).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::HeaderValue;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "0123abcd";
+
+    #[derive(Default)]
+    struct FakeBackend {
+        recording: AtomicBool,
+    }
+
+    impl Backend for FakeBackend {
+        fn is_recording(&self) -> Result<bool> {
+            Ok(self.recording.load(Ordering::SeqCst))
+        }
+
+        fn start_recording(&self, _action: HotkeyAction) -> BoxFuture<'_, Result<()>> {
+            self.recording.store(true, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn stop_and_process(&self) -> BoxFuture<'_, Result<PipelineResult>> {
+            self.recording.store(false, Ordering::SeqCst);
+            Box::pin(async { Err(VoiceFlowError::NoSpeech) })
+        }
+
+        fn cancel(&self) -> Result<()> {
+            self.recording.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn history(&self, _: i64, _: i64, _: Option<&str>) -> Result<Vec<Transcription>> {
+            Ok(Vec::new())
+        }
+
+        fn stats(&self) -> Result<TranscriptionStats> {
+            Err(VoiceFlowError::Database("No stats in tests".into()))
+        }
+
+        fn report_error(&self, _error: &VoiceFlowError) {}
+    }
+
+    async fn send(router: &Router, method: &str, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[test]
+    fn test_router_statuses() {
+        let (stopped, stopped_rx) = watch::channel(false);
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let router = router(Api {
+            backend: Arc::new(FakeBackend::default()),
+            token: Arc::new(RwLock::new(TOKEN.to_string())),
+            stopped: stopped_rx,
+            events,
+        });
+
+        tauri::async_runtime::block_on(async {
+            let unauthorized = StatusCode::UNAUTHORIZED;
+            assert_eq!(
+                send(&router, "POST", "/recording/start", None).await,
+                unauthorized
+            );
+            assert_eq!(
+                send(&router, "GET", "/history", Some("wrong")).await,
+                unauthorized
+            );
+            assert_eq!(
+                send(&router, "GET", "/history", Some(TOKEN)).await,
+                StatusCode::OK
+            );
+
+            let start = send(&router, "POST", "/recording/start", Some(TOKEN)).await;
+            assert_eq!(start, StatusCode::NO_CONTENT);
+            let again = send(&router, "POST", "/recording/start", Some(TOKEN)).await;
+            assert_eq!(again, StatusCode::CONFLICT);
+
+            let stop = send(&router, "POST", "/recording/stop", Some(TOKEN)).await;
+            assert_eq!(stop, StatusCode::UNPROCESSABLE_ENTITY);
+            let again = send(&router, "POST", "/recording/stop", Some(TOKEN)).await;
+            assert_eq!(again, StatusCode::CONFLICT);
+
+            stopped.send_replace(true);
+            let after_stop = send(&router, "GET", "/history", Some(TOKEN)).await;
+            assert_eq!(after_stop, StatusCode::SERVICE_UNAVAILABLE);
+        });
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let token = "0123abcd";
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, None, token));
+        assert!(is_authorized(&headers, Some("token=0123abcd"), token));
+        assert!(is_authorized(
+            &headers,
+            Some("limit=5&token=0123abcd"),
+            token
+        ));
+        assert!(!is_authorized(&headers, Some("token=0123abce"), token));
+        assert!(!is_authorized(&headers, Some("token="), token));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer 0123abcd"),
+        );
+        assert!(is_authorized(&headers, None, token));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("0123abcd"));
+        assert!(!is_authorized(&headers, None, token));
+    }
+
+    #[test]
+    fn test_new_token_is_unguessable() {
+        let token = new_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, new_token());
+    }
+}
@@ -1,8 +1,12 @@
-use rusqlite::{params, Connection};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Row};
 use uuid::Uuid;
 
-use crate::errors::Result;
-use crate::storage::models::{Transcription, TranscriptionStats};
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::models::{
+    AppRule, LanguageStats, PromptProfile, ReplacementRule, RuleKind, Segment, Transcription,
+    TranscriptionStats, VocabularyTerm,
+};
 
 pub struct Database {
     conn: Connection,
@@ -34,27 +38,99 @@ impl Database {
                 value TEXT NOT NULL
             );
 
+            CREATE TABLE IF NOT EXISTS vocabulary (
+                id TEXT PRIMARY KEY,
+                term TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS replacement_rules (
+                id TEXT PRIMARY KEY,
+                pattern TEXT NOT NULL,
+                replacement TEXT NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'plain',
+                case_insensitive INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                position INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS prompt_profiles (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                system_prompt TEXT NOT NULL,
+                temperature REAL NOT NULL,
+                model TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS app_rules (
+                id TEXT PRIMARY KEY,
+                window_class TEXT NOT NULL DEFAULT '',
+                window_title TEXT NOT NULL DEFAULT '',
+                profile_id TEXT,
+                language TEXT,
+                injection TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                position INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS transcription_segments (
+                transcription_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                start_ms INTEGER NOT NULL,
+                end_ms INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                confidence REAL,
+                words TEXT NOT NULL DEFAULT '[]',
+                PRIMARY KEY (transcription_id, position)
+            );
+
             CREATE INDEX IF NOT EXISTS idx_transcriptions_created
                 ON transcriptions(created_at DESC);
             ",
         )?;
+
+        // Columns added after a table was first released; CREATE TABLE IF NOT EXISTS
+        // leaves existing databases without them
+        self.add_column_if_missing("transcriptions", "app", "TEXT")?;
+        self.add_column_if_missing("transcriptions", "language", "TEXT")?;
+        Ok(())
+    }
+
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let exists = self
+            .conn
+            .prepare("SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2")?
+            .exists(params![table, column])?;
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
         Ok(())
     }
 
+    /// Store a finished dictation. `app` is the window class it was typed into and
+    /// `language` the spoken language, when known.
     pub fn save_transcription(
         &self,
         raw_text: &str,
         refined_text: &str,
         stt_latency_ms: u64,
         llm_latency_ms: u64,
+        app: Option<&str>,
+        language: Option<&str>,
     ) -> Result<String> {
         let id = Uuid::new_v4().to_string();
         let word_count = refined_text.split_whitespace().count() as i64;
 
         self.conn.execute(
-            "INSERT INTO transcriptions (id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![id, raw_text, refined_text, stt_latency_ms as i64, llm_latency_ms as i64, word_count],
+            "INSERT INTO transcriptions (id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count, app, language)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![id, raw_text, refined_text, stt_latency_ms as i64, llm_latency_ms as i64, word_count, app, language],
         )?;
 
         log::info!("Saved transcription {} ({} words)", id, word_count);
@@ -72,47 +148,27 @@ impl Database {
         if let Some(query) = search {
             let pattern = format!("%{}%", query);
             let mut stmt = self.conn.prepare(
-                "SELECT id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count, created_at
+                "SELECT id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count, created_at, app, language
                  FROM transcriptions
                  WHERE refined_text LIKE ?1 OR raw_text LIKE ?1
                  ORDER BY created_at DESC
                  LIMIT ?2 OFFSET ?3",
             )?;
 
-            let rows = stmt.query_map(params![pattern, limit, offset], |row| {
-                Ok(Transcription {
-                    id: row.get(0)?,
-                    raw_text: row.get(1)?,
-                    refined_text: row.get(2)?,
-                    stt_latency_ms: row.get(3)?,
-                    llm_latency_ms: row.get(4)?,
-                    word_count: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            })?;
+            let rows = stmt.query_map(params![pattern, limit, offset], transcription_from_row)?;
 
             for row in rows {
                 results.push(row?);
             }
         } else {
             let mut stmt = self.conn.prepare(
-                "SELECT id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count, created_at
+                "SELECT id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count, created_at, app, language
                  FROM transcriptions
                  ORDER BY created_at DESC
                  LIMIT ?1 OFFSET ?2",
             )?;
 
-            let rows = stmt.query_map(params![limit, offset], |row| {
-                Ok(Transcription {
-                    id: row.get(0)?,
-                    raw_text: row.get(1)?,
-                    refined_text: row.get(2)?,
-                    stt_latency_ms: row.get(3)?,
-                    llm_latency_ms: row.get(4)?,
-                    word_count: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            })?;
+            let rows = stmt.query_map(params![limit, offset], transcription_from_row)?;
 
             for row in rows {
                 results.push(row?);
@@ -122,9 +178,52 @@ impl Database {
         Ok(results)
     }
 
+    /// Store the timed segments of a saved transcription, in order. Word timings are
+    /// kept as JSON alongside their segment.
+    pub fn save_segments(&self, transcription_id: &str, segments: &[Segment]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, segment) in segments.iter().enumerate() {
+            let words = serde_json::to_string(&segment.words)
+                .map_err(|e| VoiceFlowError::Database(e.to_string()))?;
+            tx.execute(
+                "INSERT INTO transcription_segments (transcription_id, position, start_ms, end_ms, text, confidence, words)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    transcription_id,
+                    position as i64,
+                    segment.start_ms as i64,
+                    segment.end_ms as i64,
+                    segment.text,
+                    segment.confidence,
+                    words
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Timed segments of a transcription, in order. Empty for transcriptions made
+    /// before timings were stored or with providers that report none.
+    pub fn get_segments(&self, transcription_id: &str) -> Result<Vec<Segment>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_ms, end_ms, text, confidence, words FROM transcription_segments
+             WHERE transcription_id = ?1 ORDER BY position",
+        )?;
+        let segments = stmt
+            .query_map(params![transcription_id], segment_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(segments)
+    }
+
     pub fn delete_transcription(&self, id: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM transcriptions WHERE id = ?1", params![id])?;
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM transcription_segments WHERE transcription_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM transcriptions WHERE id = ?1", params![id])?;
+        tx.commit()?;
         Ok(())
     }
 
@@ -169,6 +268,24 @@ impl Database {
             |row| row.get(0),
         )?;
 
+        let mut stmt = self.conn.prepare(
+            "SELECT language, COUNT(*), COALESCE(SUM(word_count), 0) FROM transcriptions
+             WHERE language IS NOT NULL
+             GROUP BY language
+             ORDER BY 3 DESC, 2 DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(LanguageStats {
+                language: row.get(0)?,
+                transcriptions: row.get(1)?,
+                words: row.get(2)?,
+            })
+        })?;
+        let mut languages = Vec::new();
+        for row in rows {
+            languages.push(row?);
+        }
+
         Ok(TranscriptionStats {
             total_transcriptions,
             total_words,
@@ -177,6 +294,7 @@ impl Database {
             words_this_month,
             avg_stt_latency_ms,
             avg_llm_latency_ms,
+            languages,
         })
     }
 
@@ -202,6 +320,339 @@ impl Database {
         )?;
         Ok(())
     }
+
+    /// Add a term to the custom vocabulary. Terms are unique ignoring case; adding an
+    /// existing one returns its id unchanged.
+    pub fn add_vocabulary_term(&self, term: &str) -> Result<String> {
+        let term = term.trim();
+        if term.is_empty() {
+            return Err(VoiceFlowError::Database("Vocabulary term is empty".into()));
+        }
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO vocabulary (id, term) VALUES (?1, ?2)",
+            params![Uuid::new_v4().to_string(), term],
+        )?;
+        let id = self.conn.query_row(
+            "SELECT id FROM vocabulary WHERE term = ?1",
+            params![term],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// All vocabulary terms, oldest first.
+    pub fn get_vocabulary(&self) -> Result<Vec<VocabularyTerm>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, term, created_at FROM vocabulary ORDER BY created_at ASC, rowid ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(VocabularyTerm {
+                id: row.get(0)?,
+                term: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    pub fn delete_vocabulary_term(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM vocabulary WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Append a replacement rule after the existing ones. New rules start enabled.
+    pub fn add_replacement_rule(
+        &self,
+        pattern: &str,
+        replacement: &str,
+        kind: RuleKind,
+        case_insensitive: bool,
+    ) -> Result<ReplacementRule> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO replacement_rules (id, pattern, replacement, kind, case_insensitive, position)
+             VALUES (?1, ?2, ?3, ?4, ?5,
+                     (SELECT COALESCE(MAX(position) + 1, 0) FROM replacement_rules))",
+            params![id, pattern, replacement, kind.as_str(), case_insensitive],
+        )?;
+        let rule = self.conn.query_row(
+            "SELECT id, pattern, replacement, kind, case_insensitive, enabled, position, created_at
+             FROM replacement_rules WHERE id = ?1",
+            params![id],
+            replacement_rule_from_row,
+        )?;
+        Ok(rule)
+    }
+
+    /// All replacement rules in the order they are applied.
+    pub fn get_replacement_rules(&self) -> Result<Vec<ReplacementRule>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, pattern, replacement, kind, case_insensitive, enabled, position, created_at
+             FROM replacement_rules ORDER BY position ASC",
+        )?;
+        let rows = stmt.query_map([], replacement_rule_from_row)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// Update a rule's pattern, replacement, matching options and enable flag.
+    /// Order is changed with `reorder_replacement_rules`.
+    pub fn update_replacement_rule(&self, rule: &ReplacementRule) -> Result<()> {
+        self.conn.execute(
+            "UPDATE replacement_rules
+             SET pattern = ?2, replacement = ?3, kind = ?4, case_insensitive = ?5, enabled = ?6
+             WHERE id = ?1",
+            params![
+                rule.id,
+                rule.pattern,
+                rule.replacement,
+                rule.kind.as_str(),
+                rule.case_insensitive,
+                rule.enabled
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_replacement_rule(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM replacement_rules WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Store a new rule order: `ids[0]` runs first. Rules not listed keep their position.
+    pub fn reorder_replacement_rules(&self, ids: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE replacement_rules SET position = ?2 WHERE id = ?1",
+                params![id, position as i64],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Append an application rule after the existing ones. New rules start enabled.
+    pub fn add_app_rule(
+        &self,
+        window_class: &str,
+        window_title: &str,
+        profile_id: Option<&str>,
+        language: Option<&str>,
+        injection: Option<&str>,
+    ) -> Result<AppRule> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO app_rules (id, window_class, window_title, profile_id, language, injection, position)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6,
+                     (SELECT COALESCE(MAX(position) + 1, 0) FROM app_rules))",
+            params![id, window_class, window_title, profile_id, language, injection],
+        )?;
+        let rule = self.conn.query_row(
+            "SELECT id, window_class, window_title, profile_id, language, injection, enabled, position, created_at
+             FROM app_rules WHERE id = ?1",
+            params![id],
+            app_rule_from_row,
+        )?;
+        Ok(rule)
+    }
+
+    /// All application rules in the order they are checked.
+    pub fn get_app_rules(&self) -> Result<Vec<AppRule>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, window_class, window_title, profile_id, language, injection, enabled, position, created_at
+             FROM app_rules ORDER BY position ASC",
+        )?;
+        let rows = stmt.query_map([], app_rule_from_row)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// Update everything but a rule's position (see `reorder_app_rules`).
+    pub fn update_app_rule(&self, rule: &AppRule) -> Result<()> {
+        self.conn.execute(
+            "UPDATE app_rules
+             SET window_class = ?2, window_title = ?3, profile_id = ?4, language = ?5,
+                 injection = ?6, enabled = ?7
+             WHERE id = ?1",
+            params![
+                rule.id,
+                rule.window_class,
+                rule.window_title,
+                rule.profile_id,
+                rule.language,
+                rule.injection,
+                rule.enabled
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_app_rule(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM app_rules WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Store a new rule order: `ids[0]` is checked first.
+    pub fn reorder_app_rules(&self, ids: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE app_rules SET position = ?2 WHERE id = ?1",
+                params![id, position as i64],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn add_prompt_profile(
+        &self,
+        name: &str,
+        system_prompt: &str,
+        temperature: f32,
+        model: Option<&str>,
+    ) -> Result<PromptProfile> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO prompt_profiles (id, name, system_prompt, temperature, model)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, name, system_prompt, temperature, model],
+        )?;
+        self.get_prompt_profile(&id)?
+            .ok_or_else(|| VoiceFlowError::Database("Prompt profile was not saved".into()))
+    }
+
+    /// User-defined prompt profiles, oldest first. The built-in default is not stored.
+    pub fn get_prompt_profiles(&self) -> Result<Vec<PromptProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, system_prompt, temperature, model, created_at
+             FROM prompt_profiles ORDER BY created_at ASC, rowid ASC",
+        )?;
+        let rows = stmt.query_map([], prompt_profile_from_row)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_prompt_profile(&self, id: &str) -> Result<Option<PromptProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, system_prompt, temperature, model, created_at
+             FROM prompt_profiles WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], prompt_profile_from_row)?;
+        match rows.next() {
+            Some(row) => Ok(Some(row?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn update_prompt_profile(&self, profile: &PromptProfile) -> Result<()> {
+        self.conn.execute(
+            "UPDATE prompt_profiles
+             SET name = ?2, system_prompt = ?3, temperature = ?4, model = ?5
+             WHERE id = ?1",
+            params![
+                profile.id,
+                profile.name,
+                profile.system_prompt,
+                profile.temperature,
+                profile.model
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_prompt_profile(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM prompt_profiles WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+fn prompt_profile_from_row(row: &Row) -> rusqlite::Result<PromptProfile> {
+    Ok(PromptProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        system_prompt: row.get(2)?,
+        temperature: row.get(3)?,
+        model: row.get(4)?,
+        builtin: false,
+        created_at: row.get(5)?,
+    })
+}
+
+fn transcription_from_row(row: &Row) -> rusqlite::Result<Transcription> {
+    Ok(Transcription {
+        id: row.get(0)?,
+        raw_text: row.get(1)?,
+        refined_text: row.get(2)?,
+        stt_latency_ms: row.get(3)?,
+        llm_latency_ms: row.get(4)?,
+        word_count: row.get(5)?,
+        created_at: row.get(6)?,
+        app: row.get(7)?,
+        language: row.get(8)?,
+    })
+}
+
+fn segment_from_row(row: &Row) -> rusqlite::Result<Segment> {
+    let words: String = row.get(4)?;
+    Ok(Segment {
+        start_ms: row.get::<_, i64>(0)? as u64,
+        end_ms: row.get::<_, i64>(1)? as u64,
+        text: row.get(2)?,
+        confidence: row.get(3)?,
+        words: serde_json::from_str(&words)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, Type::Text, Box::new(e)))?,
+    })
+}
+
+fn app_rule_from_row(row: &Row) -> rusqlite::Result<AppRule> {
+    Ok(AppRule {
+        id: row.get(0)?,
+        window_class: row.get(1)?,
+        window_title: row.get(2)?,
+        profile_id: row.get(3)?,
+        language: row.get(4)?,
+        injection: row.get(5)?,
+        enabled: row.get(6)?,
+        position: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn replacement_rule_from_row(row: &Row) -> rusqlite::Result<ReplacementRule> {
+    Ok(ReplacementRule {
+        id: row.get(0)?,
+        pattern: row.get(1)?,
+        replacement: row.get(2)?,
+        kind: RuleKind::from_db(&row.get::<_, String>(3)?),
+        case_insensitive: row.get(4)?,
+        enabled: row.get(5)?,
+        position: row.get(6)?,
+        created_at: row.get(7)?,
+    })
 }
 
 #[cfg(test)]
@@ -213,7 +664,16 @@ mod tests {
         let db = Database::new(":memory:").unwrap();
 
         // Save
-        let id = db.save_transcription("hello world", "Hello, world!", 100, 200).unwrap();
+        let id = db
+            .save_transcription(
+                "hello world",
+                "Hello, world!",
+                100,
+                200,
+                Some("Slack"),
+                Some("en"),
+            )
+            .unwrap();
         assert!(!id.is_empty());
 
         // Get
@@ -221,6 +681,8 @@ mod tests {
         assert_eq!(transcriptions.len(), 1);
         assert_eq!(transcriptions[0].refined_text, "Hello, world!");
         assert_eq!(transcriptions[0].word_count, 2);
+        assert_eq!(transcriptions[0].app.as_deref(), Some("Slack"));
+        assert_eq!(transcriptions[0].language.as_deref(), Some("en"));
 
         // Search
         let found = db.get_transcriptions(10, 0, Some("Hello")).unwrap();
@@ -233,6 +695,8 @@ mod tests {
         let stats = db.get_stats().unwrap();
         assert_eq!(stats.total_transcriptions, 1);
         assert_eq!(stats.total_words, 2);
+        assert_eq!(stats.languages.len(), 1);
+        assert_eq!(stats.languages[0].language, "en");
 
         // Delete
         db.delete_transcription(&id).unwrap();
@@ -252,4 +716,196 @@ mod tests {
         db.set_setting("api_key", "new-key").unwrap();
         assert_eq!(db.get_setting("api_key").unwrap(), Some("new-key".to_string()));
     }
+
+    #[test]
+    fn test_vocabulary() {
+        let db = Database::new(":memory:").unwrap();
+
+        let k8s = db.add_vocabulary_term("Kubernetes").unwrap();
+        db.add_vocabulary_term("  pgbouncer ").unwrap();
+        // Same term with different case is not added twice
+        assert_eq!(db.add_vocabulary_term("kubernetes").unwrap(), k8s);
+        assert!(db.add_vocabulary_term("   ").is_err());
+
+        let terms: Vec<String> = db.get_vocabulary().unwrap().into_iter().map(|t| t.term).collect();
+        assert_eq!(terms, vec!["Kubernetes", "pgbouncer"]);
+
+        db.delete_vocabulary_term(&k8s).unwrap();
+        let terms: Vec<String> = db.get_vocabulary().unwrap().into_iter().map(|t| t.term).collect();
+        assert_eq!(terms, vec!["pgbouncer"]);
+    }
+
+    #[test]
+    fn test_replacement_rules() {
+        let db = Database::new(":memory:").unwrap();
+
+        let first = db
+            .add_replacement_rule("get hub", "GitHub", RuleKind::Plain, true)
+            .unwrap();
+        let second = db
+            .add_replacement_rule(r"\bv(\d+)", "version $1", RuleKind::Regex, false)
+            .unwrap();
+        assert!(first.enabled);
+        assert_eq!((first.position, second.position), (0, 1));
+
+        let mut updated = second.clone();
+        updated.enabled = false;
+        updated.kind = RuleKind::WholeWord;
+        db.update_replacement_rule(&updated).unwrap();
+
+        db.reorder_replacement_rules(&[second.id.clone(), first.id.clone()])
+            .unwrap();
+        let rules = db.get_replacement_rules().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].id, second.id);
+        assert!(!rules[0].enabled);
+        assert_eq!(rules[0].kind, RuleKind::WholeWord);
+        assert!(rules[1].case_insensitive);
+
+        db.delete_replacement_rule(&second.id).unwrap();
+        let rules = db.get_replacement_rules().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].pattern, "get hub");
+    }
+
+    #[test]
+    fn test_prompt_profiles() {
+        let db = Database::new(":memory:").unwrap();
+
+        let email = db
+            .add_prompt_profile("Email", "Write an email in {language}.", 0.5, None)
+            .unwrap();
+        assert!(!email.builtin);
+        assert_eq!(email.model, None);
+
+        let mut updated = email.clone();
+        updated.model = Some("gpt-4o".to_string());
+        updated.temperature = 0.2;
+        db.update_prompt_profile(&updated).unwrap();
+
+        let loaded = db.get_prompt_profile(&email.id).unwrap().unwrap();
+        assert_eq!(loaded.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(loaded.temperature, 0.2);
+        assert_eq!(db.get_prompt_profiles().unwrap().len(), 1);
+
+        db.delete_prompt_profile(&email.id).unwrap();
+        assert!(db.get_prompt_profile(&email.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_app_rules() {
+        let db = Database::new(":memory:").unwrap();
+
+        let terminal = db
+            .add_app_rule("terminal", "", Some("commit"), None, Some("terminal_paste"))
+            .unwrap();
+        let slack = db
+            .add_app_rule("Slack", "", None, Some("en"), None)
+            .unwrap();
+        assert!(terminal.enabled);
+        assert_eq!((terminal.position, slack.position), (0, 1));
+
+        let mut updated = slack.clone();
+        updated.window_title = "general".to_string();
+        updated.enabled = false;
+        db.update_app_rule(&updated).unwrap();
+        db.reorder_app_rules(&[slack.id.clone(), terminal.id.clone()])
+            .unwrap();
+
+        let rules = db.get_app_rules().unwrap();
+        assert_eq!(rules[0].id, slack.id);
+        assert_eq!(rules[0].window_title, "general");
+        assert!(!rules[0].enabled);
+        assert_eq!(rules[1].injection.as_deref(), Some("terminal_paste"));
+
+        db.delete_app_rule(&terminal.id).unwrap();
+        assert_eq!(db.get_app_rules().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_adds_app_column_to_existing_database() {
+        let path = std::env::temp_dir().join(format!("voiceflow-test-{}.db", Uuid::new_v4()));
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE transcriptions (
+                    id TEXT PRIMARY KEY,
+                    raw_text TEXT NOT NULL,
+                    refined_text TEXT NOT NULL,
+                    stt_latency_ms INTEGER NOT NULL,
+                    llm_latency_ms INTEGER NOT NULL,
+                    word_count INTEGER NOT NULL,
+                    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+                );
+                INSERT INTO transcriptions (id, raw_text, refined_text, stt_latency_ms, llm_latency_ms, word_count)
+                VALUES ('old', 'hi', 'Hi.', 1, 2, 1);",
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(&path.to_string_lossy()).unwrap();
+        let transcriptions = db.get_transcriptions(10, 0, None).unwrap();
+        assert_eq!(transcriptions[0].id, "old");
+        assert_eq!(transcriptions[0].app, None);
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_language_stats() {
+        let db = Database::new(":memory:").unwrap();
+        db.save_transcription("a", "um dois três", 1, 1, None, Some("pt"))
+            .unwrap();
+        db.save_transcription("b", "one two", 1, 1, None, Some("en"))
+            .unwrap();
+        db.save_transcription("c", "quatro", 1, 1, None, Some("pt"))
+            .unwrap();
+        db.save_transcription("d", "unknown", 1, 1, None, None)
+            .unwrap();
+
+        let languages = db.get_stats().unwrap().languages;
+        let summary: Vec<_> = languages
+            .iter()
+            .map(|l| (l.language.as_str(), l.transcriptions, l.words))
+            .collect();
+        assert_eq!(summary, vec![("pt", 2, 4), ("en", 1, 2)]);
+    }
+
+    #[test]
+    fn test_segments() {
+        use crate::storage::models::WordTiming;
+
+        let db = Database::new(":memory:").unwrap();
+        let id = db
+            .save_transcription("hello world", "Hello world.", 1, 1, None, None)
+            .unwrap();
+        let segments = vec![
+            Segment {
+                start_ms: 0,
+                end_ms: 900,
+                text: "Hello".to_string(),
+                confidence: Some(0.9),
+                words: vec![WordTiming {
+                    start_ms: 100,
+                    end_ms: 600,
+                    word: "Hello".to_string(),
+                    confidence: None,
+                }],
+            },
+            Segment {
+                start_ms: 900,
+                end_ms: 1800,
+                text: "world.".to_string(),
+                confidence: None,
+                words: Vec::new(),
+            },
+        ];
+        db.save_segments(&id, &segments).unwrap();
+
+        assert_eq!(db.get_segments(&id).unwrap(), segments);
+        assert!(db.get_segments("missing").unwrap().is_empty());
+
+        db.delete_transcription(&id).unwrap();
+        assert!(db.get_segments(&id).unwrap().is_empty());
+    }
 }
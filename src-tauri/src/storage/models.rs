@@ -9,6 +9,31 @@ pub struct Transcription {
     pub llm_latency_ms: i64,
     pub word_count: i64,
     pub created_at: String,
+    /// Window class of the application the text was typed into, when detected.
+    pub app: Option<String>,
+    /// Spoken language (ISO 639-1), as configured or detected.
+    pub language: Option<String>,
+}
+
+/// A stretch of speech with its position in the recording, as timed by the STT
+/// provider. Times are milliseconds from the start of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// Provider confidence between 0 and 1, when reported.
+    pub confidence: Option<f64>,
+    /// Word timings, for providers that report them.
+    pub words: Vec<WordTiming>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTiming {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub word: String,
+    pub confidence: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,4 +45,99 @@ pub struct TranscriptionStats {
     pub words_this_month: i64,
     pub avg_stt_latency_ms: f64,
     pub avg_llm_latency_ms: f64,
+    /// Usage per spoken language, most words first.
+    pub languages: Vec<LanguageStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub transcriptions: i64,
+    pub words: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VocabularyTerm {
+    pub id: String,
+    pub term: String,
+    pub created_at: String,
+}
+
+/// How a replacement rule's pattern is matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Anywhere in the text, including inside longer words.
+    Plain,
+    /// Only where not preceded or followed by a letter, digit or underscore.
+    WholeWord,
+    /// A regular expression; the replacement may use `$1` / `${name}` captures.
+    Regex,
+}
+
+impl RuleKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::WholeWord => "whole_word",
+            Self::Regex => "regex",
+        }
+    }
+
+    /// Parse the stored value; unknown values are treated as plain text.
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "whole_word" => Self::WholeWord,
+            "regex" => Self::Regex,
+            _ => Self::Plain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    pub id: String,
+    pub pattern: String,
+    pub replacement: String,
+    pub kind: RuleKind,
+    pub case_insensitive: bool,
+    pub enabled: bool,
+    /// Rules run in ascending order, each on the output of the previous one.
+    pub position: i64,
+    pub created_at: String,
+}
+
+/// A refinement prompt for a context (email, code comments, chat, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptProfile {
+    pub id: String,
+    pub name: String,
+    /// System prompt template; `{language}` and `{vocabulary}` are filled in per request.
+    pub system_prompt: String,
+    pub temperature: f32,
+    /// Refinement model to use instead of the provider's configured one.
+    pub model: Option<String>,
+    /// Shipped with the app; cannot be edited or deleted.
+    pub builtin: bool,
+    pub created_at: String,
+}
+
+/// Settings applied when dictating into a matching application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRule {
+    pub id: String,
+    /// Case-insensitive substring of the window class; empty matches any class.
+    pub window_class: String,
+    /// Case-insensitive substring of the window title; empty matches any title.
+    pub window_title: String,
+    /// Prompt profile to refine with instead of the active one.
+    pub profile_id: Option<String>,
+    /// Transcription language instead of the `language` setting.
+    pub language: Option<String>,
+    /// Injection method ("paste", "terminal_paste" or "type") instead of the default.
+    pub injection: Option<String>,
+    pub enabled: bool,
+    /// Rules are checked in ascending order; the first match wins.
+    pub position: i64,
+    pub created_at: String,
 }
@@ -0,0 +1,4 @@
+pub mod replace;
+pub mod spoken;
+pub mod stitch;
+pub mod subtitles;
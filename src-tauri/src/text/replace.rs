@@ -0,0 +1,173 @@
+//! User-defined find/replace rules applied to the final text.
+
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use crate::errors::{Result, VoiceFlowError};
+use crate::storage::models::{ReplacementRule, RuleKind};
+
+/// A replacement rule ready to run.
+#[derive(Debug)]
+pub struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    /// Whether `$1`-style references in the replacement are expanded (regex rules only).
+    expand: bool,
+}
+
+/// Compile a rule, reporting empty or invalid patterns.
+pub fn compile(rule: &ReplacementRule) -> Result<CompiledRule> {
+    Ok(CompiledRule {
+        regex: build_regex(&rule.pattern, rule.kind, rule.case_insensitive)?,
+        replacement: rule.replacement.clone(),
+        expand: rule.kind == RuleKind::Regex,
+    })
+}
+
+/// The regex that finds `pattern` for the given kind of rule.
+pub fn build_regex(pattern: &str, kind: RuleKind, case_insensitive: bool) -> Result<Regex> {
+    if pattern.is_empty() {
+        return Err(VoiceFlowError::Pipeline(
+            "Replacement pattern is empty".into(),
+        ));
+    }
+
+    let source = match kind {
+        RuleKind::Plain => regex::escape(pattern),
+        // Half boundaries only look outside the pattern, so words that start or end
+        // with punctuation ("C++", ".NET") still match. Word characters are Unicode.
+        RuleKind::WholeWord => {
+            format!(r"\b{{start-half}}{}\b{{end-half}}", regex::escape(pattern))
+        }
+        RuleKind::Regex => pattern.to_string(),
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| {
+            VoiceFlowError::Pipeline(format!("Invalid replacement pattern '{}': {}", pattern, e))
+        })
+}
+
+/// Compile the enabled rules in order. Rules that fail to compile are logged and
+/// skipped so one bad pattern does not block dictation.
+pub fn compile_enabled(rules: &[ReplacementRule]) -> Vec<CompiledRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| match compile(rule) {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                log::warn!("Skipping replacement rule {}: {}", rule.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Apply rules in order, each to the output of the previous one.
+pub fn apply(text: &str, rules: &[CompiledRule]) -> String {
+    let mut text = text.to_string();
+    for rule in rules {
+        let replaced = if rule.expand {
+            rule.regex.replace_all(&text, rule.replacement.as_str())
+        } else {
+            rule.regex.replace_all(&text, NoExpand(&rule.replacement))
+        };
+        text = replaced.into_owned();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        pattern: &str,
+        replacement: &str,
+        kind: RuleKind,
+        case_insensitive: bool,
+    ) -> ReplacementRule {
+        ReplacementRule {
+            id: pattern.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            kind,
+            case_insensitive,
+            enabled: true,
+            position: 0,
+            created_at: String::new(),
+        }
+    }
+
+    fn run(text: &str, rules: &[ReplacementRule]) -> String {
+        apply(text, &compile_enabled(rules))
+    }
+
+    #[test]
+    fn test_plain_matches_inside_words_and_is_literal() {
+        let rules = [rule("hub", "$1Hub", RuleKind::Plain, false)];
+        assert_eq!(run("github hub", &rules), "git$1Hub $1Hub");
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let js = rule("js", "JavaScript", RuleKind::WholeWord, true);
+        let ts = rule("JavaScript", "TypeScript", RuleKind::WholeWord, false);
+
+        assert_eq!(
+            run("I like JS", &[js.clone(), ts.clone()]),
+            "I like TypeScript"
+        );
+        assert_eq!(run("I like JS", &[ts, js]), "I like JavaScript");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules_are_skipped() {
+        let mut disabled = rule("foo", "bar", RuleKind::Plain, false);
+        disabled.enabled = false;
+        let invalid = rule("(unclosed", "x", RuleKind::Regex, false);
+        let valid = rule("baz", "qux", RuleKind::Plain, false);
+
+        assert!(compile(&invalid).is_err());
+        assert_eq!(run("foo baz", &[disabled, invalid, valid]), "foo qux");
+    }
+
+    #[test]
+    fn test_whole_word_uses_unicode_boundaries() {
+        // In "apiário" the match is followed by a non-ASCII letter, which an ASCII
+        // word boundary would treat as the end of the word
+        let rules = [rule("api", "API", RuleKind::WholeWord, true)];
+        assert_eq!(run("a api do apiário", &rules), "a API do apiário");
+
+        let rules = [rule("ação", "action", RuleKind::WholeWord, false)];
+        assert_eq!(run("ação reação ação.", &rules), "action reação action.");
+    }
+
+    #[test]
+    fn test_whole_word_with_punctuation_edges() {
+        let rules = [rule("c++", "C++", RuleKind::WholeWord, true)];
+        assert_eq!(
+            run("I write c++ and C++11", &rules),
+            "I write C++ and C++11"
+        );
+
+        let rules = [rule(".net", ".NET", RuleKind::WholeWord, true)];
+        assert_eq!(run("use .net here", &rules), "use .NET here");
+    }
+
+    #[test]
+    fn test_case_insensitive_folds_unicode() {
+        let rules = [rule("ÉRRO", "erro", RuleKind::WholeWord, true)];
+        assert_eq!(run("Érro e érro", &rules), "erro e erro");
+
+        let rules = [rule("ÉRRO", "erro", RuleKind::WholeWord, false)];
+        assert_eq!(run("Érro e érro", &rules), "Érro e érro");
+    }
+
+    #[test]
+    fn test_regex_expands_captures() {
+        let rules = [rule(r"\bv(\d+)", "version $1", RuleKind::Regex, false)];
+        assert_eq!(run("ship v2 today", &rules), "ship version 2 today");
+    }
+}
@@ -0,0 +1,294 @@
+//! Spoken punctuation and formatting commands ("comma", "new line", "all caps ...
+//! end caps") for raw mode, where no LLM cleans up the transcript.
+
+/// What a spoken command turns into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    /// Punctuation that sticks to the previous word, replacing any punctuation
+    /// Whisper already put there.
+    Attach(&'static str),
+    /// Opening bracket or quote: space before, none after.
+    Open(&'static str),
+    /// Line or paragraph break.
+    Break(&'static str),
+    CapsOn,
+    CapsOff,
+}
+
+use Command::*;
+
+/// Punctuation Whisper adds on its own, dropped when a spoken mark takes its place.
+const WHISPER_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?'];
+
+/// Phrases are written in folded form (see `fold`): lowercase, no accents or hyphens.
+const ENGLISH: &[(&str, Command)] = &[
+    ("period", Attach(".")),
+    ("full stop", Attach(".")),
+    ("comma", Attach(",")),
+    ("question mark", Attach("?")),
+    ("exclamation mark", Attach("!")),
+    ("exclamation point", Attach("!")),
+    ("colon", Attach(":")),
+    ("semicolon", Attach(";")),
+    ("ellipsis", Attach("...")),
+    ("new line", Break("\n")),
+    ("newline", Break("\n")),
+    ("new paragraph", Break("\n\n")),
+    ("open paren", Open("(")),
+    ("open parenthesis", Open("(")),
+    ("close paren", Attach(")")),
+    ("close parenthesis", Attach(")")),
+    ("open bracket", Open("[")),
+    ("close bracket", Attach("]")),
+    ("open brace", Open("{")),
+    ("close brace", Attach("}")),
+    ("open quote", Open("\"")),
+    ("close quote", Attach("\"")),
+    ("all caps", CapsOn),
+    ("end caps", CapsOff),
+];
+
+const PORTUGUESE: &[(&str, Command)] = &[
+    ("ponto final", Attach(".")),
+    ("virgula", Attach(",")),
+    ("ponto de interrogacao", Attach("?")),
+    ("ponto de exclamacao", Attach("!")),
+    ("dois pontos", Attach(":")),
+    ("ponto e virgula", Attach(";")),
+    ("reticencias", Attach("...")),
+    ("nova linha", Break("\n")),
+    ("novo paragrafo", Break("\n\n")),
+    ("abre parenteses", Open("(")),
+    ("fecha parenteses", Attach(")")),
+    ("abre colchetes", Open("[")),
+    ("fecha colchetes", Attach("]")),
+    ("abre chaves", Open("{")),
+    ("fecha chaves", Attach("}")),
+    ("abre aspas", Open("\"")),
+    ("fecha aspas", Attach("\"")),
+    ("tudo maiusculo", CapsOn),
+    ("caixa alta", CapsOn),
+    ("fim maiusculo", CapsOff),
+    ("fim caixa alta", CapsOff),
+];
+
+const SPANISH: &[(&str, Command)] = &[
+    ("punto final", Attach(".")),
+    ("punto y seguido", Attach(".")),
+    ("coma", Attach(",")),
+    ("abre interrogacion", Open("¿")),
+    ("cierra interrogacion", Attach("?")),
+    ("abre exclamacion", Open("¡")),
+    ("cierra exclamacion", Attach("!")),
+    ("dos puntos", Attach(":")),
+    ("punto y coma", Attach(";")),
+    ("puntos suspensivos", Attach("...")),
+    ("nueva linea", Break("\n")),
+    ("punto y aparte", Break("\n")),
+    ("nuevo parrafo", Break("\n\n")),
+    ("abre parentesis", Open("(")),
+    ("cierra parentesis", Attach(")")),
+    ("abre corchetes", Open("[")),
+    ("cierra corchetes", Attach("]")),
+    ("abre llaves", Open("{")),
+    ("cierra llaves", Attach("}")),
+    ("abre comillas", Open("\"")),
+    ("cierra comillas", Attach("\"")),
+    ("todo mayusculas", CapsOn),
+    ("fin mayusculas", CapsOff),
+];
+
+/// Command table for a transcription language; other languages use English.
+fn commands_for(language: &str) -> &'static [(&'static str, Command)] {
+    match language {
+        "pt" => PORTUGUESE,
+        "es" => SPANISH,
+        _ => ENGLISH,
+    }
+}
+
+/// Replace spoken commands in `text` with the punctuation or formatting they name.
+pub fn apply(text: &str, language: &str) -> String {
+    let commands = commands_for(language);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let folded: Vec<String> = words.iter().map(|w| fold(w)).collect();
+
+    let mut out = String::new();
+    // No space before the next word (after an opening bracket or a line break)
+    let mut glue_next = true;
+    let mut capitalize_next = false;
+    let mut caps = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        if let Some((len, command)) = match_command(&folded[i..], commands) {
+            match command {
+                Attach(mark) => {
+                    let kept = out.trim_end_matches(WHISPER_PUNCTUATION).len();
+                    out.truncate(kept);
+                    out.push_str(mark);
+                    glue_next = false;
+                    capitalize_next = matches!(mark, "." | "?" | "!");
+                }
+                Open(mark) => {
+                    if !glue_next {
+                        out.push(' ');
+                    }
+                    out.push_str(mark);
+                    glue_next = true;
+                }
+                Break(breaks) => {
+                    out.push_str(breaks);
+                    glue_next = true;
+                    capitalize_next = true;
+                }
+                CapsOn => caps = true,
+                CapsOff => caps = false,
+            }
+            i += len;
+            continue;
+        }
+
+        if !glue_next {
+            out.push(' ');
+        }
+        let word = if caps {
+            words[i].to_uppercase()
+        } else if capitalize_next {
+            capitalize(words[i])
+        } else {
+            words[i].to_string()
+        };
+        out.push_str(&word);
+        glue_next = false;
+        capitalize_next = false;
+        i += 1;
+    }
+    out
+}
+
+/// Longest command starting at the first word: (words consumed, command).
+fn match_command(words: &[String], commands: &[(&str, Command)]) -> Option<(usize, Command)> {
+    let mut best: Option<(usize, Command)> = None;
+    for &(phrase, command) in commands {
+        let len = phrase.split(' ').count();
+        if len > words.len() || best.is_some_and(|(best_len, _)| best_len >= len) {
+            continue;
+        }
+        if phrase.split(' ').zip(words).all(|(p, w)| p == w) {
+            best = Some((len, command));
+        }
+    }
+    best
+}
+
+/// Comparison form of a word: lowercase letters and digits only, accents removed,
+/// so "Vírgula," and "semi-colon" match "virgula" and "semicolon".
+fn fold(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' => 'a',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c => c,
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_punctuation() {
+        assert_eq!(
+            apply("hello comma world period how are you question mark", "en"),
+            "hello, world. How are you?"
+        );
+    }
+
+    #[test]
+    fn test_replaces_whisper_punctuation_around_commands() {
+        // Whisper tends to punctuate around the spoken words themselves
+        assert_eq!(apply("Hello, comma, world. Period.", "en"), "Hello, world.");
+        assert_eq!(
+            apply("First item. New line. Second item.", "en"),
+            "First item.\nSecond item."
+        );
+    }
+
+    #[test]
+    fn test_lines_and_paragraphs() {
+        assert_eq!(
+            apply("dear team new paragraph the build is green", "en"),
+            "dear team\n\nThe build is green"
+        );
+    }
+
+    #[test]
+    fn test_brackets_and_quotes() {
+        assert_eq!(
+            apply("call foo open paren bar close paren now", "en"),
+            "call foo (bar) now"
+        );
+        assert_eq!(
+            apply("he said open quote ship it close quote", "en"),
+            "he said \"ship it\""
+        );
+    }
+
+    #[test]
+    fn test_all_caps() {
+        assert_eq!(
+            apply("set all caps api key end caps now", "en"),
+            "set API KEY now"
+        );
+        // Without an end marker caps run to the end
+        assert_eq!(apply("all caps done", "en"), "DONE");
+    }
+
+    #[test]
+    fn test_portuguese_prefers_longest_phrase() {
+        assert_eq!(
+            apply("olá vírgula tudo bem ponto de interrogação", "pt"),
+            "olá, tudo bem?"
+        );
+        assert_eq!(
+            apply("primeiro ponto e vírgula segundo ponto final", "pt"),
+            "primeiro; segundo."
+        );
+        // A lone "ponto" is an ordinary word
+        assert_eq!(apply("ponto de vista", "pt"), "ponto de vista");
+    }
+
+    #[test]
+    fn test_spanish() {
+        assert_eq!(
+            apply(
+                "abre interrogación qué tal cierra interrogación nueva línea adiós",
+                "es"
+            ),
+            "¿qué tal?\nAdiós"
+        );
+    }
+
+    #[test]
+    fn test_commands_are_language_specific() {
+        assert_eq!(apply("uno coma dos", "en"), "uno coma dos");
+        assert_eq!(apply("one comma two", "de"), "one, two");
+    }
+}
@@ -0,0 +1,280 @@
+//! Joining per-chunk transcripts back into a single text.
+
+use std::ops::Range;
+
+use crate::storage::models::Segment;
+
+/// Words searched on each side of a chunk boundary for the repeated overlap.
+const OVERLAP_WINDOW_WORDS: usize = 20;
+
+/// Join chunk transcripts in order. Parts flagged as overlapping the previous chunk
+/// have the words that were transcribed twice removed.
+pub fn stitch<I>(parts: I) -> String
+where
+    I: IntoIterator<Item = (String, bool)>,
+{
+    let mut text = String::new();
+    for (part, overlaps_previous) in parts {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        text = if text.is_empty() {
+            part.to_string()
+        } else if overlaps_previous {
+            merge_overlap(&text, part)
+        } else {
+            format!("{} {}", text, part)
+        };
+    }
+    text
+}
+
+/// Put chunk segments on one timeline. Each part is `(segments, chunk offset in ms,
+/// overlaps previous)`. In an overlapping part, segments centred before the previous
+/// part's last segment ended were heard twice and are dropped.
+pub fn stitch_segments<I>(parts: I) -> Vec<Segment>
+where
+    I: IntoIterator<Item = (Vec<Segment>, u64, bool)>,
+{
+    let mut timeline: Vec<Segment> = Vec::new();
+    for (segments, offset_ms, overlaps_previous) in parts {
+        let seam = timeline.last().map_or(0, |s| s.end_ms);
+        for mut segment in segments {
+            segment.start_ms += offset_ms;
+            segment.end_ms += offset_ms;
+            for word in &mut segment.words {
+                word.start_ms += offset_ms;
+                word.end_ms += offset_ms;
+            }
+            if overlaps_previous && (segment.start_ms + segment.end_ms) / 2 < seam {
+                continue;
+            }
+            timeline.push(segment);
+        }
+    }
+    timeline
+}
+
+/// Merge two transcripts whose audio overlapped. The longest run of words shared by
+/// the end of `previous` and the start of `next` is kept once; partial words on either
+/// side of it (cut mid-word at the chunk edge) are dropped.
+fn merge_overlap(previous: &str, next: &str) -> String {
+    let prev_spans = word_spans(previous);
+    let next_spans = word_spans(next);
+    let prev_tail = &prev_spans[prev_spans.len().saturating_sub(OVERLAP_WINDOW_WORDS)..];
+    let next_head = &next_spans[..next_spans.len().min(OVERLAP_WINDOW_WORDS)];
+
+    let prev_words: Vec<String> = prev_tail
+        .iter()
+        .map(|r| normalize(&previous[r.clone()]))
+        .collect();
+    let next_words: Vec<String> = next_head
+        .iter()
+        .map(|r| normalize(&next[r.clone()]))
+        .collect();
+
+    let Some((prev_start, next_start, len)) = longest_common_run(&prev_words, &next_words) else {
+        return format!("{} {}", previous, next);
+    };
+    // A single shared word is only trusted right at the seam
+    if len == 1 && (prev_start + 1 != prev_words.len() || next_start != 0) {
+        return format!("{} {}", previous, next);
+    }
+
+    // Keep the first shared word as `previous` wrote it (mid-sentence casing) and the
+    // rest as `next` did (it heard what followed, so its punctuation is better).
+    let keep_prev = prev_tail[prev_start].end;
+    let resume_next = next_head[next_start].end;
+    format!("{}{}", &previous[..keep_prev], &next[resume_next..])
+}
+
+/// Longest contiguous run of equal, non-empty words: (start in `a`, start in `b`, length).
+fn longest_common_run(a: &[String], b: &[String]) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+    let mut lengths = vec![0usize; b.len() + 1];
+    for (i, word) in a.iter().enumerate() {
+        // Iterate backwards so `lengths[j]` still holds the value from row i - 1
+        for j in (0..b.len()).rev() {
+            lengths[j + 1] = if !word.is_empty() && *word == b[j] {
+                lengths[j] + 1
+            } else {
+                0
+            };
+            let len = lengths[j + 1];
+            let longer = match best {
+                Some((_, _, best_len)) => len > best_len,
+                None => len > 0,
+            };
+            if longer {
+                best = Some((i + 1 - len, j + 1 - len, len));
+            }
+        }
+    }
+    best
+}
+
+/// Byte ranges of whitespace-separated words.
+fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..text.len());
+    }
+    spans
+}
+
+/// Case- and punctuation-insensitive form of a word for comparison.
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::WordTiming;
+
+    fn parts(items: &[(&str, bool)]) -> Vec<(String, bool)> {
+        items.iter().map(|&(t, o)| (t.to_string(), o)).collect()
+    }
+
+    #[test]
+    fn test_joins_non_overlapping_parts() {
+        let text = stitch(parts(&[("First part.", false), ("Second part.", false)]));
+        assert_eq!(text, "First part. Second part.");
+    }
+
+    #[test]
+    fn test_skips_empty_parts() {
+        let text = stitch(parts(&[
+            ("", false),
+            ("Hello.", false),
+            ("  ", true),
+            ("World.", false),
+        ]));
+        assert_eq!(text, "Hello. World.");
+    }
+
+    #[test]
+    fn test_removes_repeated_words() {
+        let text = stitch(parts(&[
+            ("we should deploy the new", false),
+            ("deploy the new service tomorrow", true),
+        ]));
+        assert_eq!(text, "we should deploy the new service tomorrow");
+    }
+
+    #[test]
+    fn test_drops_partial_words_at_the_seam() {
+        let text = stitch(parts(&[
+            ("we should deploy the new serv", false),
+            ("ew service tomorrow", true),
+        ]));
+        // Nothing shared but a fragment: keep both rather than guess
+        assert_eq!(text, "we should deploy the new serv ew service tomorrow");
+
+        let text = stitch(parts(&[
+            ("we should deploy the new serv", false),
+            ("o deploy the new service tomorrow", true),
+        ]));
+        assert_eq!(text, "we should deploy the new service tomorrow");
+    }
+
+    #[test]
+    fn test_prefers_mid_sentence_casing_and_later_punctuation() {
+        let text = stitch(parts(&[
+            ("and then we ship it.", false),
+            ("Ship it to production on Friday.", true),
+        ]));
+        assert_eq!(text, "and then we ship it to production on Friday.");
+    }
+
+    #[test]
+    fn test_unicode_words_match_case_insensitively() {
+        let text = stitch(parts(&[
+            ("precisamos revisar a configuração", false),
+            ("A configuração do servidor", true),
+        ]));
+        assert_eq!(text, "precisamos revisar a configuração do servidor");
+    }
+
+    #[test]
+    fn test_single_word_only_matches_at_the_seam() {
+        let text = stitch(parts(&[("the cat sat", false), ("sat down", true)]));
+        assert_eq!(text, "the cat sat down");
+
+        let text = stitch(parts(&[("the cat sat", false), ("on the mat", true)]));
+        assert_eq!(text, "the cat sat on the mat");
+    }
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            confidence: None,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_segments_are_shifted_onto_one_timeline() {
+        let mut first = segment(0, 900, "one");
+        first.words.push(WordTiming {
+            start_ms: 100,
+            end_ms: 400,
+            word: "one".to_string(),
+            confidence: None,
+        });
+        let timeline = stitch_segments(vec![
+            (vec![first], 500, false),
+            (vec![segment(0, 800, "two")], 2000, false),
+        ]);
+
+        let spans: Vec<_> = timeline.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+        assert_eq!(spans, [(500, 1400), (2000, 2800)]);
+        assert_eq!(timeline[0].words[0].start_ms, 600);
+    }
+
+    #[test]
+    fn test_overlapping_segments_are_dropped() {
+        let timeline = stitch_segments(vec![
+            (
+                vec![segment(0, 4000, "a"), segment(4000, 10000, "b")],
+                0,
+                false,
+            ),
+            // Starts 2s before the previous chunk ended
+            (
+                vec![segment(0, 1500, "b again"), segment(1500, 5000, "c")],
+                8000,
+                true,
+            ),
+        ]);
+
+        let texts: Vec<_> = timeline.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_overlap_covering_whole_next_part() {
+        let text = stitch(parts(&[
+            ("one two three four", false),
+            ("three four", true),
+        ]));
+        assert_eq!(text, "one two three four");
+    }
+}
@@ -0,0 +1,238 @@
+//! SubRip (`.srt`) and WebVTT (`.vtt`) captions from timed transcript segments.
+
+use crate::storage::models::Segment;
+
+/// Longest caption line, the usual broadcast limit that stays readable on small screens.
+const MAX_LINE_CHARS: usize = 42;
+/// Lines shown at once; longer segments are split into several cues.
+const MAX_LINES: usize = 2;
+
+/// One caption on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+/// `HH:MM:SS,mmm` for SubRip, `HH:MM:SS.mmm` for WebVTT. Hours are not capped at 99.
+pub fn format_timestamp(ms: u64, format: SubtitleFormat) -> String {
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Render cues as a complete subtitle file.
+pub fn render(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (i, cue) in cues.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp(cue.start_ms, format),
+            format_timestamp(cue.end_ms, format)
+        ));
+        for line in &cue.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Break segments into cues of at most `MAX_LINES` wrapped lines. A segment that
+/// needs several cues is timed by its word timings when they line up with its
+/// text, and by character position otherwise.
+pub fn cues(segments: &[Segment]) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    for segment in segments {
+        let words: Vec<&str> = segment.text.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let timed = segment.words.len() == words.len();
+        let total_chars = segment.text.chars().count().max(1) as u64;
+        let duration = segment.end_ms.saturating_sub(segment.start_ms);
+        // Time of the point `chars` characters into the segment text
+        let at_char = |chars: usize| {
+            segment.start_ms + duration * (chars as u64).min(total_chars) / total_chars
+        };
+
+        let lines = wrap(&words, MAX_LINE_CHARS);
+        let mut first_word = 0;
+        let mut chars_before = 0;
+        for group in lines.chunks(MAX_LINES) {
+            let word_count: usize = group.iter().map(|l| l.split(' ').count()).sum();
+            let chars: usize = group.iter().map(|l| l.chars().count() + 1).sum();
+            let last_word = first_word + word_count - 1;
+            let (start_ms, end_ms) = if timed {
+                (
+                    segment.words[first_word].start_ms,
+                    segment.words[last_word].end_ms,
+                )
+            } else {
+                (at_char(chars_before), at_char(chars_before + chars))
+            };
+            cues.push(Cue {
+                start_ms,
+                end_ms: end_ms.max(start_ms + 1),
+                lines: group.to_vec(),
+            });
+            first_word = last_word + 1;
+            chars_before += chars;
+        }
+    }
+    cues
+}
+
+/// Greedy word wrap. A word longer than `width` gets a line of its own.
+fn wrap(words: &[&str], width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in words {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::WordTiming;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            confidence: None,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0, SubtitleFormat::Srt), "00:00:00,000");
+        assert_eq!(
+            format_timestamp(3_723_004, SubtitleFormat::Srt),
+            "01:02:03,004"
+        );
+        assert_eq!(
+            format_timestamp(59_999, SubtitleFormat::Vtt),
+            "00:00:59.999"
+        );
+        assert_eq!(
+            format_timestamp(100 * 3_600_000, SubtitleFormat::Vtt),
+            "100:00:00.000"
+        );
+    }
+
+    #[test]
+    fn test_wrap_respects_line_length() {
+        let words: Vec<&str> = "the quick brown fox jumps over the lazy dog"
+            .split(' ')
+            .collect();
+        assert_eq!(
+            wrap(&words, 15),
+            ["the quick brown", "fox jumps over", "the lazy dog"]
+        );
+        assert_eq!(
+            wrap(&["supercalifragilistic", "ok"], 10),
+            ["supercalifragilistic", "ok"]
+        );
+    }
+
+    #[test]
+    fn test_render_srt_and_vtt() {
+        let cues = cues(&[
+            segment(1500, 4000, "Hello there."),
+            segment(4000, 5250, "Bye."),
+        ]);
+
+        assert_eq!(
+            render(&cues, SubtitleFormat::Srt),
+            "1\n00:00:01,500 --> 00:00:04,000\nHello there.\n\n\
+             2\n00:00:04,000 --> 00:00:05,250\nBye.\n\n"
+        );
+        assert_eq!(
+            render(&cues, SubtitleFormat::Vtt),
+            "WEBVTT\n\n\
+             00:00:01.500 --> 00:00:04.000\nHello there.\n\n\
+             00:00:04.000 --> 00:00:05.250\nBye.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_long_segment_is_split_into_cues() {
+        let text = "one two three four five six seven eight nine ten eleven twelve thirteen \
+                    fourteen fifteen sixteen seventeen eighteen nineteen twenty";
+        let cues = cues(&[segment(0, 10_000, text)]);
+
+        assert_eq!(cues.len(), 2);
+        assert!(cues.iter().all(
+            |c| c.lines.len() <= MAX_LINES && c.lines.iter().all(|l| l.len() <= MAX_LINE_CHARS)
+        ));
+        // Timed by character position, back to back
+        assert_eq!(cues[0].start_ms, 0);
+        assert_eq!(cues[0].end_ms, cues[1].start_ms);
+        assert!(cues[1].end_ms >= 9_900);
+    }
+
+    #[test]
+    fn test_cues_use_word_timings() {
+        let words = ["alpha", "beta", "gamma"];
+        let mut timed = segment(0, 3000, "alpha beta gamma");
+        timed.words = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| WordTiming {
+                start_ms: 200 + i as u64 * 900,
+                end_ms: 800 + i as u64 * 900,
+                word: w.to_string(),
+                confidence: None,
+            })
+            .collect();
+
+        let cues = cues(&[timed]);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (200, 2600));
+    }
+}
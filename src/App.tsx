@@ -38,7 +38,8 @@ function AppContent() {
   useEffect(() => {
     loadSettings().then(() => {
       const state = useSettingsStore.getState();
-      if (!state.apiKey) {
+      // Other providers are set up deliberately in Settings; only Groq users need onboarding
+      if (!state.apiKey && state.sttProvider === "groq") {
         setShowOnboarding(true);
       }
       applyTheme(state.darkMode);
@@ -0,0 +1,200 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { ChevronDown, ChevronUp, Crosshair, Trash2 } from "lucide-react";
+import toast from "react-hot-toast";
+import type { ActiveWindow, AppRule, PromptProfile } from "../types";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  inputStyle: React.CSSProperties;
+}
+
+const iconButton = "p-1.5 rounded-md transition-all duration-150 disabled:opacity-30";
+const languages = ["auto", "pt", "en", "es", "fr", "de", "it", "ja", "ko", "zh"];
+const injections = [
+  { value: "paste", label: "settings.appRuleInjectionPaste" },
+  { value: "terminal_paste", label: "settings.appRuleInjectionTerminal" },
+  { value: "type", label: "settings.appRuleInjectionType" },
+];
+
+export default function AppRules({ inputStyle }: Props) {
+  const { t } = useTranslation();
+  const [rules, setRules] = useState<AppRule[]>([]);
+  const [profiles, setProfiles] = useState<PromptProfile[]>([]);
+  const [lastWindow, setLastWindow] = useState<ActiveWindow | null>(null);
+  const [windowClass, setWindowClass] = useState("");
+  const [windowTitle, setWindowTitle] = useState("");
+
+  const load = () => {
+    invoke<AppRule[]>("get_app_rules")
+      .then(setRules)
+      .catch((err) => console.error("Failed to load app rules:", err));
+  };
+
+  useEffect(() => {
+    load();
+    invoke<PromptProfile[]>("get_prompt_profiles").then(setProfiles).catch(() => {});
+    invoke<ActiveWindow | null>("get_active_window").then(setLastWindow).catch(() => {});
+  }, []);
+
+  const handleAdd = async () => {
+    try {
+      await invoke("add_app_rule", {
+        windowClass,
+        windowTitle,
+        profileId: null,
+        language: null,
+        injection: null,
+      });
+      setWindowClass("");
+      setWindowTitle("");
+      load();
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const handleUpdate = async (rule: AppRule, changes: Partial<AppRule>) => {
+    try {
+      await invoke("update_app_rule", { rule: { ...rule, ...changes } });
+    } catch (err) {
+      toast.error(String(err));
+    }
+    load();
+  };
+
+  const handleDelete = async (id: string) => {
+    await invoke("delete_app_rule", { id });
+    load();
+  };
+
+  const handleMove = async (index: number, offset: number) => {
+    const reordered = [...rules];
+    const [rule] = reordered.splice(index, 1);
+    reordered.splice(index + offset, 0, rule);
+    setRules(reordered);
+    await invoke("reorder_app_rules", { ids: reordered.map((r) => r.id) });
+  };
+
+  const smallInput = { ...inputStyle, padding: "6px 10px" };
+  const smallSelect = { ...inputStyle, width: "auto", padding: "6px 8px", fontSize: "12px" };
+
+  return (
+    <div className="space-y-2">
+      {rules.map((rule, index) => (
+        <div key={rule.id} className="space-y-1.5" style={{ opacity: rule.enabled ? 1 : 0.5 }}>
+          <div className="flex items-center gap-2">
+            <input
+              type="checkbox"
+              checked={rule.enabled}
+              onChange={(e) => handleUpdate(rule, { enabled: e.target.checked })}
+              title={t("settings.replacementEnabled")}
+            />
+            <input
+              type="text"
+              defaultValue={rule.window_class}
+              onBlur={(e) => e.target.value !== rule.window_class && handleUpdate(rule, { window_class: e.target.value })}
+              placeholder={t("settings.appRuleClass")}
+              className="input-branded"
+              style={{ ...smallInput, flex: 1, fontFamily: "var(--font-mono)" }}
+            />
+            <input
+              type="text"
+              defaultValue={rule.window_title}
+              onBlur={(e) => e.target.value !== rule.window_title && handleUpdate(rule, { window_title: e.target.value })}
+              placeholder={t("settings.appRuleTitle")}
+              className="input-branded"
+              style={{ ...smallInput, flex: 1 }}
+            />
+            <button onClick={() => handleMove(index, -1)} disabled={index === 0} className={iconButton} style={{ color: "var(--color-text-muted)" }}>
+              <ChevronUp className="w-3.5 h-3.5" />
+            </button>
+            <button onClick={() => handleMove(index, 1)} disabled={index === rules.length - 1} className={iconButton} style={{ color: "var(--color-text-muted)" }}>
+              <ChevronDown className="w-3.5 h-3.5" />
+            </button>
+            <button onClick={() => handleDelete(rule.id)} title={t("settings.remove")} className={iconButton} style={{ color: "var(--color-error)" }}>
+              <Trash2 className="w-3.5 h-3.5" />
+            </button>
+          </div>
+          <div className="flex items-center gap-2 pl-6">
+            <select
+              value={rule.profile_id ?? ""}
+              onChange={(e) => handleUpdate(rule, { profile_id: e.target.value || null })}
+              className="input-branded"
+              style={smallSelect}
+            >
+              <option value="">{t("settings.appRuleDefaultProfile")}</option>
+              {profiles.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.builtin ? t("settings.promptProfileDefault") : p.name}
+                </option>
+              ))}
+            </select>
+            <select
+              value={rule.language ?? ""}
+              onChange={(e) => handleUpdate(rule, { language: e.target.value || null })}
+              className="input-branded"
+              style={smallSelect}
+            >
+              <option value="">{t("settings.appRuleDefaultLanguage")}</option>
+              {languages.map((code) => (
+                <option key={code} value={code}>
+                  {t(`settings.languages.${code}`)}
+                </option>
+              ))}
+            </select>
+            <select
+              value={rule.injection ?? "paste"}
+              onChange={(e) => handleUpdate(rule, { injection: e.target.value === "paste" ? null : e.target.value })}
+              className="input-branded"
+              style={smallSelect}
+            >
+              {injections.map((i) => (
+                <option key={i.value} value={i.value}>
+                  {t(i.label)}
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-2 pt-1">
+        <input
+          type="text"
+          value={windowClass}
+          onChange={(e) => setWindowClass(e.target.value)}
+          placeholder={t("settings.appRuleClass")}
+          className="input-branded"
+          style={{ ...smallInput, flex: 1, fontFamily: "var(--font-mono)" }}
+        />
+        <input
+          type="text"
+          value={windowTitle}
+          onChange={(e) => setWindowTitle(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder={t("settings.appRuleTitle")}
+          className="input-branded"
+          style={{ ...smallInput, flex: 1 }}
+        />
+        {lastWindow && (
+          <button
+            onClick={() => setWindowClass(lastWindow.class)}
+            title={t("settings.appRuleUseLast", { app: lastWindow.class })}
+            className={iconButton}
+            style={{ color: "var(--color-text-muted)" }}
+          >
+            <Crosshair className="w-3.5 h-3.5" />
+          </button>
+        )}
+        <button
+          onClick={handleAdd}
+          className="px-3 py-1.5 rounded-lg text-sm font-medium text-white transition-all duration-150"
+          style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+        >
+          {t("settings.add")}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,60 @@
+import { useState } from "react";
+import type { HotkeyAction, HotkeyError } from "../types";
+import { useSettingsStore } from "../stores/settingsStore";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  action: HotkeyAction;
+  value: string;
+  placeholder: string;
+  inputStyle: React.CSSProperties;
+}
+
+/** Text field plus save button for one of the global hotkeys. */
+export default function HotkeyField({ action, value, placeholder, inputStyle }: Props) {
+  const { t } = useTranslation();
+  const setHotkey = useSettingsStore((s) => s.setHotkey);
+  const [local, setLocal] = useState(value);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSave = async () => {
+    try {
+      await setHotkey(local, action);
+      setError(null);
+    } catch (err) {
+      const e = err as HotkeyError;
+      setError(
+        t(e.kind === "invalid" ? "settings.hotkeyInvalid" : "settings.hotkeyRejected", {
+          reason: e.reason,
+        }),
+      );
+    }
+  };
+
+  return (
+    <>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={local}
+          onChange={(e) => setLocal(e.target.value)}
+          placeholder={placeholder}
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1 }}
+        />
+        <button
+          onClick={handleSave}
+          className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+          style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+        >
+          {t("settings.save")}
+        </button>
+      </div>
+      {error && (
+        <p className="text-xs mt-2" style={{ color: "var(--color-error)" }}>
+          {error}
+        </p>
+      )}
+    </>
+  );
+}
@@ -0,0 +1,56 @@
+import { Languages } from "lucide-react";
+import type { TranscriptionStats } from "../types";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  stats: TranscriptionStats;
+}
+
+export default function LanguageStats({ stats }: Props) {
+  const { t } = useTranslation();
+  const totalWords = stats.languages.reduce((sum, l) => sum + l.words, 0);
+
+  // Detected languages outside the settings list show as their code
+  const name = (code: string) => {
+    const key = `settings.languages.${code}`;
+    const label = t(key);
+    return label === key ? code.toUpperCase() : label;
+  };
+
+  return (
+    <div
+      className="rounded-xl p-4 transition-all duration-150"
+      style={{
+        background: "var(--color-card-gradient)",
+        border: "1px solid var(--color-border)",
+        boxShadow: `0 1px 3px var(--color-shadow-base)`,
+      }}
+    >
+      <div className="flex items-center gap-2 mb-3">
+        <Languages className="w-4 h-4" style={{ color: "var(--color-brand-light)" }} />
+        <h3 className="font-semibold text-sm">{t("languageStats.title")}</h3>
+      </div>
+      <div className="space-y-2">
+        {stats.languages.map((l) => {
+          const share = totalWords > 0 ? (l.words / totalWords) * 100 : 0;
+          return (
+            <div key={l.language}>
+              <div className="flex items-center justify-between text-xs mb-1">
+                <span style={{ color: "var(--color-text-secondary)" }}>{name(l.language)}</span>
+                <span style={{ color: "var(--color-text-muted)" }}>
+                  {t("languageStats.words", { count: l.words.toLocaleString() })} · {Math.round(share)}%
+                </span>
+              </div>
+              <div className="h-1.5 rounded-full" style={{ background: "var(--color-input-bg)" }}>
+                <div
+                  className="h-1.5 rounded-full"
+                  style={{ width: `${share}%`, background: "linear-gradient(90deg, #1E6FFF 0%, #06B6D4 100%)" }}
+                />
+              </div>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
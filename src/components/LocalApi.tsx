@@ -0,0 +1,109 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Copy, RefreshCw } from "lucide-react";
+import toast from "react-hot-toast";
+import Toggle from "./Toggle";
+import type { ApiServerStatus } from "../types";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  inputStyle: React.CSSProperties;
+}
+
+const iconButton = "px-3 py-2 rounded-lg transition-all duration-150";
+const iconButtonStyle = { border: "1px solid rgba(30, 111, 255, 0.25)", color: "var(--color-text-secondary)" };
+
+export default function LocalApi({ inputStyle }: Props) {
+  const { t } = useTranslation();
+  const [status, setStatus] = useState<ApiServerStatus | null>(null);
+  const [port, setPort] = useState("");
+
+  useEffect(() => {
+    invoke<ApiServerStatus>("get_api_server")
+      .then((s) => {
+        setStatus(s);
+        setPort(String(s.port));
+      })
+      .catch((err) => console.error("Failed to load local API settings:", err));
+  }, []);
+
+  if (!status) return null;
+
+  const apply = async (enabled: boolean) => {
+    try {
+      const s = await invoke<ApiServerStatus>("set_api_server", { enabled, port: Number(port) });
+      setStatus(s);
+      setPort(String(s.port));
+    } catch (err) {
+      toast.error(t("toast.error", { message: String(err) }));
+      invoke<ApiServerStatus>("get_api_server").then(setStatus).catch(() => {});
+    }
+  };
+
+  const regenerate = async () => {
+    try {
+      setStatus(await invoke<ApiServerStatus>("regenerate_api_token"));
+    } catch (err) {
+      toast.error(t("toast.error", { message: String(err) }));
+    }
+  };
+
+  const copyToken = async () => {
+    try {
+      await navigator.clipboard.writeText(status.token);
+      toast.success(t("card.copied"));
+    } catch {
+      toast.error(t("card.copyFailed"));
+    }
+  };
+
+  const portValid = /^\d+$/.test(port) && Number(port) >= 1 && Number(port) <= 65535;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+          {status.running
+            ? t("settings.localApiRunning", { port: status.port })
+            : t("settings.localApiStopped")}
+        </span>
+        <Toggle checked={status.enabled} onChange={() => portValid && apply(!status.enabled)} />
+      </div>
+      <div className="flex gap-2 items-center">
+        <label className="text-xs shrink-0" style={{ color: "var(--color-text-secondary)" }}>
+          {t("settings.localApiPort")}
+        </label>
+        <input
+          type="number"
+          min={1}
+          max={65535}
+          value={port}
+          onChange={(e) => setPort(e.target.value)}
+          onBlur={() => portValid && Number(port) !== status.port && apply(status.enabled)}
+          className="input-branded"
+          style={{ ...inputStyle, width: "120px", padding: "6px 10px" }}
+        />
+      </div>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={status.token}
+          readOnly
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1, fontFamily: "var(--font-mono)", fontSize: "12px" }}
+        />
+        <button onClick={copyToken} title={t("card.copy")} className={iconButton} style={iconButtonStyle}>
+          <Copy className="w-4 h-4" />
+        </button>
+        <button
+          onClick={regenerate}
+          title={t("settings.localApiRegenerate")}
+          className={iconButton}
+          style={iconButtonStyle}
+        >
+          <RefreshCw className="w-4 h-4" />
+        </button>
+      </div>
+    </div>
+  );
+}
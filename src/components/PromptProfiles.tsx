@@ -0,0 +1,165 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Copy, Trash2 } from "lucide-react";
+import toast from "react-hot-toast";
+import type { PromptProfile } from "../types";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  inputStyle: React.CSSProperties;
+  activeId: string;
+  onSelect: (id: string) => void;
+}
+
+const buttonGradient = "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)";
+
+export default function PromptProfiles({ inputStyle, activeId, onSelect }: Props) {
+  const { t } = useTranslation();
+  const [profiles, setProfiles] = useState<PromptProfile[]>([]);
+  const [draft, setDraft] = useState<PromptProfile | null>(null);
+
+  const load = () => {
+    invoke<PromptProfile[]>("get_prompt_profiles")
+      .then(setProfiles)
+      .catch((err) => console.error("Failed to load prompt profiles:", err));
+  };
+
+  useEffect(load, []);
+
+  // A deleted or unknown profile shows as the built-in one, like the backend does
+  const active = profiles.find((p) => p.id === activeId) ?? profiles[0];
+
+  useEffect(() => {
+    setDraft(active ?? null);
+  }, [active]);
+
+  const handleDuplicate = async () => {
+    if (!active) return;
+    try {
+      const created = await invoke<PromptProfile>("add_prompt_profile", {
+        name: `${active.name} ${t("settings.promptProfileCopySuffix")}`,
+        systemPrompt: active.system_prompt,
+        temperature: active.temperature,
+        model: active.model,
+      });
+      load();
+      onSelect(created.id);
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const handleSave = async () => {
+    if (!draft) return;
+    try {
+      await invoke("update_prompt_profile", { profile: draft });
+      toast.success(t("settings.promptProfileSaved"));
+      load();
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!active) return;
+    await invoke("delete_prompt_profile", { id: active.id });
+    load();
+    onSelect("default");
+  };
+
+  if (!active || !draft) return null;
+  const readOnly = active.builtin;
+
+  return (
+    <div className="space-y-2">
+      <div className="flex gap-2">
+        <select
+          value={active.id}
+          onChange={(e) => onSelect(e.target.value)}
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1 }}
+        >
+          {profiles.map((p) => (
+            <option key={p.id} value={p.id}>
+              {p.builtin ? t("settings.promptProfileDefault") : p.name}
+            </option>
+          ))}
+        </select>
+        <button
+          onClick={handleDuplicate}
+          title={t("settings.promptProfileDuplicate")}
+          className="px-3 py-2 rounded-lg transition-all duration-150"
+          style={{ border: "1px solid rgba(30, 111, 255, 0.25)", color: "var(--color-text-secondary)" }}
+        >
+          <Copy className="w-4 h-4" />
+        </button>
+        {!readOnly && (
+          <button
+            onClick={handleDelete}
+            title={t("settings.remove")}
+            className="px-3 py-2 rounded-lg transition-all duration-150"
+            style={{ border: "1px solid rgba(30, 111, 255, 0.25)", color: "var(--color-error)" }}
+          >
+            <Trash2 className="w-4 h-4" />
+          </button>
+        )}
+      </div>
+
+      {!readOnly && (
+        <input
+          type="text"
+          value={draft.name}
+          onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+          placeholder={t("settings.promptProfileName")}
+          className="input-branded"
+          style={inputStyle}
+        />
+      )}
+      <textarea
+        value={draft.system_prompt}
+        onChange={(e) => setDraft({ ...draft, system_prompt: e.target.value })}
+        readOnly={readOnly}
+        rows={8}
+        className="input-branded"
+        style={{ ...inputStyle, fontFamily: "var(--font-mono)", fontSize: "12px", resize: "vertical", opacity: readOnly ? 0.7 : 1 }}
+      />
+      <div className="flex gap-2">
+        <label className="flex items-center gap-2 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+          {t("settings.promptProfileTemperature")}
+          <input
+            type="number"
+            min={0}
+            max={2}
+            step={0.1}
+            value={draft.temperature}
+            onChange={(e) => setDraft({ ...draft, temperature: Number(e.target.value) })}
+            readOnly={readOnly}
+            className="input-branded"
+            style={{ ...inputStyle, width: "80px", padding: "6px 10px" }}
+          />
+        </label>
+        <input
+          type="text"
+          value={draft.model ?? ""}
+          onChange={(e) => setDraft({ ...draft, model: e.target.value || null })}
+          readOnly={readOnly}
+          placeholder={t("settings.promptProfileModel")}
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1, padding: "6px 10px" }}
+        />
+        {!readOnly && (
+          <button
+            onClick={handleSave}
+            className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+            style={{ background: buttonGradient }}
+          >
+            {t("settings.save")}
+          </button>
+        )}
+      </div>
+      <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+        {t(readOnly ? "settings.promptProfileBuiltinDesc" : "settings.promptProfileDesc")}
+      </p>
+    </div>
+  );
+}
@@ -18,6 +18,7 @@ export default function RecordingIndicator({ state }: Props) {
     transcribing: t("recording.transcribing"),
     refining: t("recording.refining"),
     injecting: t("recording.injecting"),
+    cancelled: t("recording.cancelled"),
   };
 
   if (state === "idle") return null;
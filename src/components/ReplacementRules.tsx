@@ -0,0 +1,158 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { ArrowRight, ChevronDown, ChevronUp, Trash2 } from "lucide-react";
+import toast from "react-hot-toast";
+import type { ReplacementRule, RuleKind } from "../types";
+import { useTranslation } from "../i18n";
+
+interface Props {
+  inputStyle: React.CSSProperties;
+}
+
+const iconButton = "p-1.5 rounded-md transition-all duration-150 disabled:opacity-30";
+
+export default function ReplacementRules({ inputStyle }: Props) {
+  const { t } = useTranslation();
+  const [rules, setRules] = useState<ReplacementRule[]>([]);
+  const [pattern, setPattern] = useState("");
+  const [replacement, setReplacement] = useState("");
+  const [kind, setKind] = useState<RuleKind>("whole_word");
+  const [caseInsensitive, setCaseInsensitive] = useState(true);
+
+  const load = () => {
+    invoke<ReplacementRule[]>("get_replacement_rules")
+      .then(setRules)
+      .catch((err) => console.error("Failed to load replacement rules:", err));
+  };
+
+  useEffect(load, []);
+
+  const handleAdd = async () => {
+    if (!pattern) return;
+    try {
+      await invoke("add_replacement_rule", { pattern, replacement, kind, caseInsensitive });
+      setPattern("");
+      setReplacement("");
+      load();
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const handleUpdate = async (rule: ReplacementRule, changes: Partial<ReplacementRule>) => {
+    try {
+      await invoke("update_replacement_rule", { rule: { ...rule, ...changes } });
+    } catch (err) {
+      toast.error(String(err));
+    }
+    load();
+  };
+
+  const handleDelete = async (id: string) => {
+    await invoke("delete_replacement_rule", { id });
+    load();
+  };
+
+  const handleMove = async (index: number, offset: number) => {
+    const reordered = [...rules];
+    const [rule] = reordered.splice(index, 1);
+    reordered.splice(index + offset, 0, rule);
+    setRules(reordered);
+    await invoke("reorder_replacement_rules", { ids: reordered.map((r) => r.id) });
+  };
+
+  const kindSelect = (value: RuleKind, onChange: (kind: RuleKind) => void) => (
+    <select
+      value={value}
+      onChange={(e) => onChange(e.target.value as RuleKind)}
+      className="input-branded"
+      style={{ ...inputStyle, width: "auto", padding: "6px 8px", fontSize: "12px" }}
+    >
+      <option value="plain">{t("settings.replacementKindPlain")}</option>
+      <option value="whole_word">{t("settings.replacementKindWholeWord")}</option>
+      <option value="regex">{t("settings.replacementKindRegex")}</option>
+    </select>
+  );
+
+  const caseToggle = (checked: boolean, onChange: (checked: boolean) => void) => (
+    <label
+      className="flex items-center gap-1 text-xs whitespace-nowrap"
+      title={t("settings.replacementCaseInsensitive")}
+      style={{ color: "var(--color-text-secondary)" }}
+    >
+      <input type="checkbox" checked={checked} onChange={(e) => onChange(e.target.checked)} />
+      Aa
+    </label>
+  );
+
+  return (
+    <div className="space-y-2">
+      {rules.map((rule, index) => (
+        <div key={rule.id} className="flex items-center gap-2" style={{ opacity: rule.enabled ? 1 : 0.5 }}>
+          <input
+            type="checkbox"
+            checked={rule.enabled}
+            onChange={(e) => handleUpdate(rule, { enabled: e.target.checked })}
+            title={t("settings.replacementEnabled")}
+          />
+          <input
+            type="text"
+            defaultValue={rule.pattern}
+            onBlur={(e) => e.target.value !== rule.pattern && handleUpdate(rule, { pattern: e.target.value })}
+            className="input-branded"
+            style={{ ...inputStyle, flex: 1, padding: "6px 10px", fontFamily: rule.kind === "regex" ? "var(--font-mono)" : undefined }}
+          />
+          <ArrowRight className="w-3.5 h-3.5 shrink-0" style={{ color: "var(--color-text-muted)" }} />
+          <input
+            type="text"
+            defaultValue={rule.replacement}
+            onBlur={(e) => e.target.value !== rule.replacement && handleUpdate(rule, { replacement: e.target.value })}
+            className="input-branded"
+            style={{ ...inputStyle, flex: 1, padding: "6px 10px" }}
+          />
+          {kindSelect(rule.kind, (kind) => handleUpdate(rule, { kind }))}
+          {caseToggle(rule.case_insensitive, (value) => handleUpdate(rule, { case_insensitive: value }))}
+          <button onClick={() => handleMove(index, -1)} disabled={index === 0} className={iconButton} style={{ color: "var(--color-text-muted)" }}>
+            <ChevronUp className="w-3.5 h-3.5" />
+          </button>
+          <button onClick={() => handleMove(index, 1)} disabled={index === rules.length - 1} className={iconButton} style={{ color: "var(--color-text-muted)" }}>
+            <ChevronDown className="w-3.5 h-3.5" />
+          </button>
+          <button onClick={() => handleDelete(rule.id)} title={t("settings.remove")} className={iconButton} style={{ color: "var(--color-error)" }}>
+            <Trash2 className="w-3.5 h-3.5" />
+          </button>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-2 pt-1">
+        <input
+          type="text"
+          value={pattern}
+          onChange={(e) => setPattern(e.target.value)}
+          placeholder={t("settings.replacementFind")}
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1, padding: "6px 10px" }}
+        />
+        <ArrowRight className="w-3.5 h-3.5 shrink-0" style={{ color: "var(--color-text-muted)" }} />
+        <input
+          type="text"
+          value={replacement}
+          onChange={(e) => setReplacement(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder={t("settings.replacementReplace")}
+          className="input-branded"
+          style={{ ...inputStyle, flex: 1, padding: "6px 10px" }}
+        />
+        {kindSelect(kind, setKind)}
+        {caseToggle(caseInsensitive, setCaseInsensitive)}
+        <button
+          onClick={handleAdd}
+          className="px-3 py-1.5 rounded-lg text-sm font-medium text-white transition-all duration-150"
+          style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+        >
+          {t("settings.add")}
+        </button>
+      </div>
+    </div>
+  );
+}
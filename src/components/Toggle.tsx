@@ -0,0 +1,24 @@
+interface ToggleProps {
+  checked: boolean;
+  onChange: () => void;
+  activeGradient?: string;
+}
+
+export default function Toggle({
+  checked,
+  onChange,
+  activeGradient = "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)",
+}: ToggleProps) {
+  return (
+    <button
+      onClick={onChange}
+      className="relative w-11 h-6 rounded-full transition-colors duration-150"
+      style={{ background: checked ? activeGradient : "rgba(100, 116, 139, 0.3)" }}
+    >
+      <span
+        className="absolute top-0.5 left-0.5 w-5 h-5 bg-white rounded-full shadow transition-transform duration-150"
+        style={{ transform: checked ? "translateX(20px)" : "none" }}
+      />
+    </button>
+  );
+}
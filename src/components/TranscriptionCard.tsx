@@ -1,5 +1,5 @@
 import { useState } from "react";
-import { Copy, Trash2, ChevronDown, ChevronUp, Clock } from "lucide-react";
+import { Copy, Trash2, ChevronDown, ChevronUp, Clock, AppWindow } from "lucide-react";
 import toast from "react-hot-toast";
 import type { Transcription } from "../types";
 import { useTranslation } from "../i18n";
@@ -61,6 +61,13 @@ export default function TranscriptionCard({ transcription, onDelete }: Props) {
       <div className="flex items-center gap-3 mt-2 text-xs" style={{ color: "var(--color-text-muted)" }}>
         <span>{formatDate(transcription.created_at)}</span>
         <span>{t("card.words", { count: transcription.word_count })}</span>
+        {transcription.language && <span>{transcription.language.toUpperCase()}</span>}
+        {transcription.app && (
+          <span className="flex items-center gap-1">
+            <AppWindow className="w-3 h-3" />
+            {transcription.app}
+          </span>
+        )}
         <span className="flex items-center gap-1">
           <Clock className="w-3 h-3" />
           <span
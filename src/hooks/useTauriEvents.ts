@@ -40,6 +40,13 @@ export function useTauriEvents() {
           toast.error(t("toast.error", { message: event.payload }));
         }),
       );
+
+      unlisteners.push(
+        await listen<string>("input-device-fallback", (event) => {
+          const { t } = getTranslation(useSettingsStore.getState().uiLanguage);
+          toast(t("toast.deviceFallback", { name: event.payload }));
+        }),
+      );
     }
 
     setup();
@@ -1,15 +1,20 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import toast from "react-hot-toast";
 import { usePipelineStore } from "../stores/pipelineStore";
 import TranscriptionCard from "../components/TranscriptionCard";
 import MetricsWidget from "../components/MetricsWidget";
 import CostTracker from "../components/CostTracker";
+import LanguageStats from "../components/LanguageStats";
 import RecordingIndicator from "../components/RecordingIndicator";
 import { Search, Mic, Zap } from "lucide-react";
 import { useSettingsStore } from "../stores/settingsStore";
 import type { Transcription, TranscriptionStats } from "../types";
 import { useTranslation } from "../i18n";
 
+const AUDIO_EXTENSIONS = ["wav", "flac", "mp3", "ogg", "oga", "opus", "m4a"];
+
 const RECORD_STYLES = {
   recording: {
     style: { background: "var(--color-error)" } as React.CSSProperties,
@@ -118,6 +123,26 @@ export default function Dashboard() {
     }
   }, [lastResult, loadTranscriptions, loadStats]);
 
+  // Audio files dropped on the window go through the pipeline like a recording
+  useEffect(() => {
+    const unlisten = getCurrentWebview().onDragDropEvent(async (event) => {
+      if (event.payload.type !== "drop") return;
+      const files = event.payload.paths.filter((path) =>
+        AUDIO_EXTENSIONS.includes(path.split(".").pop()?.toLowerCase() ?? ""),
+      );
+      for (const path of files) {
+        try {
+          await invoke("transcribe_file", { path });
+        } catch (err) {
+          toast.error(t("toast.error", { message: String(err) }));
+        }
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [t]);
+
   const handleDelete = useCallback(async (id: string) => {
     try {
       await invoke("delete_transcription", { id });
@@ -184,6 +209,9 @@ export default function Dashboard() {
       {/* Cost tracker */}
       {stats && <CostTracker stats={stats} />}
 
+      {/* Per-language usage */}
+      {stats && stats.languages.length > 0 && <LanguageStats stats={stats} />}
+
       {/* Search */}
       <div className="flex gap-2">
         <div className="relative flex-1">
@@ -243,6 +271,7 @@ export default function Dashboard() {
                 );
               })()}
             </p>
+            <p className="text-xs mt-3">{t("dashboard.dropHint")}</p>
           </div>
         ) : (
           transcriptions.map((t) => (
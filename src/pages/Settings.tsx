@@ -1,7 +1,15 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useSettingsStore } from "../stores/settingsStore";
-import { Settings as SettingsIcon, Key, Mic, Brain, Globe, Keyboard, Moon, Power, Zap } from "lucide-react";
+import { Settings as SettingsIcon, Key, Mic, Brain, Globe, Keyboard, Moon, Power, Zap, Headphones, Scissors, FileAudio, BookOpen, X, Replace, Type, MessageSquareText, AppWindow, WandSparkles, Languages, Plug } from "lucide-react";
 import { useTranslation } from "../i18n";
+import ReplacementRules from "../components/ReplacementRules";
+import PromptProfiles from "../components/PromptProfiles";
+import AppRules from "../components/AppRules";
+import HotkeyField from "../components/HotkeyField";
+import LocalApi from "../components/LocalApi";
+import Toggle from "../components/Toggle";
+import type { HotkeyError, InputDevice, VocabularyTerm } from "../types";
 
 const inputStyle: React.CSSProperties = {
   background: "var(--color-input-bg)",
@@ -25,31 +33,6 @@ const cardStyle: React.CSSProperties = {
   boxShadow: "0 1px 3px var(--color-shadow-base)",
 };
 
-interface ToggleProps {
-  checked: boolean;
-  onChange: () => void;
-  activeGradient?: string;
-}
-
-function Toggle({
-  checked,
-  onChange,
-  activeGradient = "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)",
-}: ToggleProps) {
-  return (
-    <button
-      onClick={onChange}
-      className="relative w-11 h-6 rounded-full transition-colors duration-150"
-      style={{ background: checked ? activeGradient : "rgba(100, 116, 139, 0.3)" }}
-    >
-      <span
-        className="absolute top-0.5 left-0.5 w-5 h-5 bg-white rounded-full shadow transition-transform duration-150"
-        style={{ transform: checked ? "translateX(20px)" : "none" }}
-      />
-    </button>
-  );
-}
-
 export default function Settings() {
   const {
     apiKey,
@@ -58,11 +41,31 @@ export default function Settings() {
     language,
     uiLanguage,
     hotkey,
+    hotkeyMode,
+    commandHotkey,
+    translateHotkey,
+    outputLanguage,
     darkMode,
     autostart,
     rawMode,
+    spokenCommands,
+    vad,
+    inputDevice,
+    uploadFormat,
+    sttProvider,
+    openaiBaseUrl,
+    openaiSttModel,
+    deepgramModel,
+    whisperModelPath,
+    refineProvider,
+    refineOpenaiBaseUrl,
+    refineOpenaiModel,
+    ollamaBaseUrl,
+    ollamaModel,
+    promptProfile,
     loading,
     setApiKey,
+    setHotkey,
     setSetting,
     toggleDarkMode,
   } = useSettingsStore();
@@ -70,6 +73,42 @@ export default function Settings() {
 
   const [localApiKey, setLocalApiKey] = useState(apiKey);
   const [showKey, setShowKey] = useState(false);
+  const [devices, setDevices] = useState<InputDevice[]>([]);
+  const [localHotkey, setLocalHotkey] = useState(hotkey);
+  const [hotkeyError, setHotkeyError] = useState<string | null>(null);
+  const [providerKey, setProviderKey] = useState("");
+  const [refineKey, setRefineKey] = useState("");
+  const [vocabulary, setVocabulary] = useState<VocabularyTerm[]>([]);
+  const [newTerm, setNewTerm] = useState("");
+
+  useEffect(() => {
+    invoke<InputDevice[]>("list_input_devices")
+      .then(setDevices)
+      .catch((err) => console.error("Failed to list input devices:", err));
+  }, []);
+
+  const loadVocabulary = () => {
+    invoke<VocabularyTerm[]>("get_vocabulary")
+      .then(setVocabulary)
+      .catch((err) => console.error("Failed to load vocabulary:", err));
+  };
+
+  useEffect(loadVocabulary, []);
+
+  useEffect(() => {
+    // Groq's key is edited in the API Key card above; other providers have their own
+    if (sttProvider === "groq" || sttProvider === "local") return;
+    invoke<string>("load_api_key", { provider: sttProvider })
+      .then(setProviderKey)
+      .catch(() => setProviderKey(""));
+  }, [sttProvider]);
+
+  useEffect(() => {
+    if (refineProvider !== "openai") return;
+    invoke<string>("load_api_key", { provider: "openai_refine" })
+      .then(setRefineKey)
+      .catch(() => setRefineKey(""));
+  }, [refineProvider]);
 
   if (loading) {
     return (
@@ -83,6 +122,41 @@ export default function Settings() {
     await setApiKey(localApiKey);
   };
 
+  const handleSaveProviderKey = async () => {
+    await invoke("save_api_key", { key: providerKey, provider: sttProvider });
+  };
+
+  const handleSaveRefineKey = async () => {
+    await invoke("save_api_key", { key: refineKey, provider: "openai_refine" });
+  };
+
+  const handleAddTerm = async () => {
+    const term = newTerm.trim();
+    if (!term) return;
+    await invoke("add_vocabulary_term", { term });
+    setNewTerm("");
+    loadVocabulary();
+  };
+
+  const handleDeleteTerm = async (id: string) => {
+    await invoke("delete_vocabulary_term", { id });
+    loadVocabulary();
+  };
+
+  const handleSaveHotkey = async () => {
+    try {
+      await setHotkey(localHotkey);
+      setHotkeyError(null);
+    } catch (err) {
+      const e = err as HotkeyError;
+      setHotkeyError(
+        t(e.kind === "invalid" ? "settings.hotkeyInvalid" : "settings.hotkeyRejected", {
+          reason: e.reason,
+        }),
+      );
+    }
+  };
+
   return (
     <div className="p-6 max-w-2xl mx-auto space-y-5">
       <div className="flex items-center gap-2 mb-8">
@@ -130,6 +204,32 @@ export default function Settings() {
         </p>
       </section>
 
+      {/* Input Device */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <Headphones className="w-4 h-4" style={{ color: "var(--color-brand-cyan)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.inputDevice")}</h2>
+        </div>
+        <select
+          value={inputDevice}
+          onChange={(e) => setSetting("input_device", e.target.value)}
+          className="input-branded"
+          style={inputStyle}
+        >
+          <option value="">{t("settings.inputDeviceDefault")}</option>
+          {devices.map((d) => (
+            <option key={d.name} value={d.name}>
+              {d.is_default ? `${d.name} ★` : d.name}
+            </option>
+          ))}
+        </select>
+        {inputDevice && devices.length > 0 && !devices.some((d) => d.name === inputDevice) && (
+          <p className="text-xs mt-2" style={{ color: "var(--color-warning)" }}>
+            {t("settings.inputDeviceMissing")}
+          </p>
+        )}
+      </section>
+
       {/* STT Model */}
       <section style={cardStyle}>
         <div className="flex items-center gap-2 mb-3">
@@ -137,14 +237,114 @@ export default function Settings() {
           <h2 className="font-semibold text-sm">{t("settings.sttModel")}</h2>
         </div>
         <select
-          value={sttModel}
-          onChange={(e) => setSetting("stt_model", e.target.value)}
+          value={sttProvider}
+          onChange={(e) => setSetting("stt_provider", e.target.value)}
+          className="input-branded mb-3"
+          style={inputStyle}
+        >
+          <option value="groq">Groq</option>
+          <option value="openai">{t("settings.sttProviderOpenai")}</option>
+          <option value="deepgram">Deepgram</option>
+          <option value="local">{t("settings.sttProviderLocal")}</option>
+        </select>
+        {sttProvider === "groq" && (
+          <select
+            value={sttModel}
+            onChange={(e) => setSetting("stt_model", e.target.value)}
+            className="input-branded"
+            style={inputStyle}
+          >
+            <option value="whisper-large-v3">Whisper Large v3</option>
+            <option value="whisper-large-v3-turbo">Whisper Large v3 Turbo</option>
+            <option value="distil-whisper-large-v3-en">Distil Whisper Large v3 (EN)</option>
+          </select>
+        )}
+        {sttProvider === "openai" && (
+          <div className="space-y-2">
+            <input
+              type="text"
+              defaultValue={openaiBaseUrl}
+              onBlur={(e) => setSetting("openai_base_url", e.target.value.trim())}
+              placeholder="https://api.openai.com/v1"
+              className="input-branded"
+              style={inputStyle}
+            />
+            <input
+              type="text"
+              defaultValue={openaiSttModel}
+              onBlur={(e) => setSetting("openai_stt_model", e.target.value.trim())}
+              placeholder="whisper-1"
+              className="input-branded"
+              style={inputStyle}
+            />
+          </div>
+        )}
+        {sttProvider === "deepgram" && (
+          <input
+            type="text"
+            defaultValue={deepgramModel}
+            onBlur={(e) => setSetting("deepgram_model", e.target.value.trim())}
+            placeholder="nova-2"
+            className="input-branded"
+            style={inputStyle}
+          />
+        )}
+        {sttProvider === "local" && (
+          <>
+            <input
+              type="text"
+              defaultValue={whisperModelPath}
+              onBlur={(e) => setSetting("whisper_model_path", e.target.value.trim())}
+              placeholder="/path/to/ggml-large-v3-turbo.bin"
+              className="input-branded"
+              style={inputStyle}
+            />
+            <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+              {t("settings.sttProviderLocalDesc")}
+            </p>
+          </>
+        )}
+        {(sttProvider === "openai" || sttProvider === "deepgram") && (
+          <>
+            <div className="flex gap-2 mt-2">
+              <input
+                type="password"
+                value={providerKey}
+                onChange={(e) => setProviderKey(e.target.value)}
+                placeholder={t("settings.sttProviderKey")}
+                className="input-branded"
+                style={{ ...inputStyle, flex: 1 }}
+              />
+              <button
+                onClick={handleSaveProviderKey}
+                className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+                style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+              >
+                {t("settings.save")}
+              </button>
+            </div>
+            <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+              {t(sttProvider === "openai" ? "settings.sttProviderOpenaiDesc" : "settings.sttProviderDeepgramDesc")}
+            </p>
+          </>
+        )}
+      </section>
+
+      {/* Upload Format */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <FileAudio className="w-4 h-4" style={{ color: "var(--color-text-muted)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.uploadFormat")}</h2>
+        </div>
+        <select
+          value={uploadFormat}
+          onChange={(e) => setSetting("upload_format", e.target.value)}
           className="input-branded"
           style={inputStyle}
         >
-          <option value="whisper-large-v3">Whisper Large v3</option>
-          <option value="whisper-large-v3-turbo">Whisper Large v3 Turbo</option>
-          <option value="distil-whisper-large-v3-en">Distil Whisper Large v3 (EN)</option>
+          <option value="wav">{t("settings.uploadFormatWav")}</option>
+          <option value="flac">{t("settings.uploadFormatFlac")}</option>
+          <option value="opus">{t("settings.uploadFormatOpus")}</option>
         </select>
       </section>
 
@@ -155,28 +355,115 @@ export default function Settings() {
           <h2 className="font-semibold text-sm">{t("settings.llmModel")}</h2>
         </div>
         <select
-          value={llmModel}
-          onChange={(e) => setSetting("llm_model", e.target.value)}
-          className="input-branded"
+          value={refineProvider}
+          onChange={(e) => setSetting("refine_provider", e.target.value)}
+          className="input-branded mb-3"
           style={inputStyle}
         >
-          <optgroup label="Production — Meta">
-            <option value="llama-3.3-70b-versatile">Llama 3.3 70B Versatile</option>
-            <option value="llama-3.1-8b-instant">Llama 3.1 8B Instant</option>
-          </optgroup>
-          <optgroup label="Production — OpenAI GPT-OSS">
-            <option value="openai/gpt-oss-120b">GPT-OSS 120B</option>
-            <option value="openai/gpt-oss-20b">GPT-OSS 20B</option>
-          </optgroup>
-          <optgroup label="Preview — Meta Llama 4">
-            <option value="meta-llama/llama-4-maverick-17b-128e-instruct">Llama 4 Maverick 17B</option>
-            <option value="meta-llama/llama-4-scout-17b-16e-instruct">Llama 4 Scout 17B</option>
-          </optgroup>
-          <optgroup label="Preview — Outros">
-            <option value="qwen/qwen3-32b">Qwen 3 32B</option>
-            <option value="moonshotai/kimi-k2-instruct-0905">Kimi K2</option>
-          </optgroup>
+          <option value="groq">Groq</option>
+          <option value="openai">{t("settings.sttProviderOpenai")}</option>
+          <option value="ollama">Ollama</option>
         </select>
+        {refineProvider === "groq" && (
+          <select
+            value={llmModel}
+            onChange={(e) => setSetting("llm_model", e.target.value)}
+            className="input-branded"
+            style={inputStyle}
+          >
+            <optgroup label="Production — Meta">
+              <option value="llama-3.3-70b-versatile">Llama 3.3 70B Versatile</option>
+              <option value="llama-3.1-8b-instant">Llama 3.1 8B Instant</option>
+            </optgroup>
+            <optgroup label="Production — OpenAI GPT-OSS">
+              <option value="openai/gpt-oss-120b">GPT-OSS 120B</option>
+              <option value="openai/gpt-oss-20b">GPT-OSS 20B</option>
+            </optgroup>
+            <optgroup label="Preview — Meta Llama 4">
+              <option value="meta-llama/llama-4-maverick-17b-128e-instruct">Llama 4 Maverick 17B</option>
+              <option value="meta-llama/llama-4-scout-17b-16e-instruct">Llama 4 Scout 17B</option>
+            </optgroup>
+            <optgroup label="Preview — Outros">
+              <option value="qwen/qwen3-32b">Qwen 3 32B</option>
+              <option value="moonshotai/kimi-k2-instruct-0905">Kimi K2</option>
+            </optgroup>
+          </select>
+        )}
+        {refineProvider === "openai" && (
+          <div className="space-y-2">
+            <input
+              type="text"
+              defaultValue={refineOpenaiBaseUrl}
+              onBlur={(e) => setSetting("refine_openai_base_url", e.target.value.trim())}
+              placeholder="http://localhost:8080/v1"
+              className="input-branded"
+              style={inputStyle}
+            />
+            <input
+              type="text"
+              defaultValue={refineOpenaiModel}
+              onBlur={(e) => setSetting("refine_openai_model", e.target.value.trim())}
+              placeholder="gpt-4o-mini"
+              className="input-branded"
+              style={inputStyle}
+            />
+            <div className="flex gap-2">
+              <input
+                type="password"
+                value={refineKey}
+                onChange={(e) => setRefineKey(e.target.value)}
+                placeholder={t("settings.refineProviderKey")}
+                className="input-branded"
+                style={{ ...inputStyle, flex: 1 }}
+              />
+              <button
+                onClick={handleSaveRefineKey}
+                className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+                style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+              >
+                {t("settings.save")}
+              </button>
+            </div>
+          </div>
+        )}
+        {refineProvider === "ollama" && (
+          <div className="space-y-2">
+            <input
+              type="text"
+              defaultValue={ollamaBaseUrl}
+              onBlur={(e) => setSetting("ollama_base_url", e.target.value.trim())}
+              placeholder="http://localhost:11434"
+              className="input-branded"
+              style={inputStyle}
+            />
+            <input
+              type="text"
+              defaultValue={ollamaModel}
+              onBlur={(e) => setSetting("ollama_model", e.target.value.trim())}
+              placeholder="llama3.2"
+              className="input-branded"
+              style={inputStyle}
+            />
+          </div>
+        )}
+        {refineProvider !== "groq" && (
+          <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+            {t("settings.refineProviderLocalDesc")}
+          </p>
+        )}
+      </section>
+
+      {/* Prompt Profiles */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <MessageSquareText className="w-4 h-4" style={{ color: "var(--color-brand)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.promptProfile")}</h2>
+        </div>
+        <PromptProfiles
+          inputStyle={inputStyle}
+          activeId={promptProfile}
+          onSelect={(id) => setSetting("prompt_profile", id)}
+        />
       </section>
 
       {/* Transcription Language */}
@@ -191,6 +478,7 @@ export default function Settings() {
           className="input-branded"
           style={inputStyle}
         >
+          <option value="auto">{t("settings.languages.auto")}</option>
           <option value="pt">{t("settings.languages.pt")}</option>
           <option value="en">{t("settings.languages.en")}</option>
           <option value="es">{t("settings.languages.es")}</option>
@@ -203,6 +491,79 @@ export default function Settings() {
         </select>
       </section>
 
+      {/* Vocabulary */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <BookOpen className="w-4 h-4" style={{ color: "var(--color-brand-cyan)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.vocabulary")}</h2>
+        </div>
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={newTerm}
+            onChange={(e) => setNewTerm(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && handleAddTerm()}
+            placeholder={t("settings.vocabularyPlaceholder")}
+            className="input-branded"
+            style={{ ...inputStyle, flex: 1 }}
+          />
+          <button
+            onClick={handleAddTerm}
+            className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+            style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+          >
+            {t("settings.add")}
+          </button>
+        </div>
+        {vocabulary.length > 0 && (
+          <div className="flex flex-wrap gap-2 mt-3">
+            {vocabulary.map((v) => (
+              <span
+                key={v.id}
+                className="flex items-center gap-1 pl-3 pr-1.5 py-1 rounded-full text-xs"
+                style={{ background: "var(--color-input-bg)", border: "1px solid var(--color-border)" }}
+              >
+                {v.term}
+                <button
+                  onClick={() => handleDeleteTerm(v.id)}
+                  title={t("settings.remove")}
+                  style={{ color: "var(--color-text-muted)" }}
+                >
+                  <X className="w-3 h-3" />
+                </button>
+              </span>
+            ))}
+          </div>
+        )}
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.vocabularyDesc")}
+        </p>
+      </section>
+
+      {/* Replacement Rules */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <Replace className="w-4 h-4" style={{ color: "var(--color-warning)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.replacements")}</h2>
+        </div>
+        <ReplacementRules inputStyle={inputStyle} />
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.replacementsDesc")}
+        </p>
+      </section>
+
+      {/* App Rules */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <AppWindow className="w-4 h-4" style={{ color: "var(--color-brand-light)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.appRules")}</h2>
+        </div>
+        <AppRules inputStyle={inputStyle} />
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.appRulesDesc")}
+        </p>
+      </section>
+
       {/* Interface Language */}
       <section style={cardStyle}>
         <div className="flex items-center gap-2 mb-3">
@@ -227,15 +588,91 @@ export default function Settings() {
           <Keyboard className="w-4 h-4" style={{ color: "var(--color-brand-light)" }} />
           <h2 className="font-semibold text-sm">{t("settings.hotkey")}</h2>
         </div>
-        <input
-          type="text"
-          value={hotkey}
-          readOnly
-          style={{ ...inputStyle, cursor: "default" }}
-        />
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={localHotkey}
+            onChange={(e) => setLocalHotkey(e.target.value)}
+            placeholder="Ctrl+Shift+Space"
+            className="input-branded"
+            style={{ ...inputStyle, flex: 1 }}
+          />
+          <button
+            onClick={handleSaveHotkey}
+            className="px-4 py-2 rounded-lg text-sm font-medium text-white transition-all duration-150"
+            style={{ background: "linear-gradient(135deg, #1E6FFF 0%, #0EA5E9 100%)" }}
+          >
+            {t("settings.save")}
+          </button>
+        </div>
+        {hotkeyError && (
+          <p className="text-xs mt-2" style={{ color: "var(--color-error)" }}>
+            {hotkeyError}
+          </p>
+        )}
         <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
           {t("settings.hotkeyDesc")}
         </p>
+        <select
+          value={hotkeyMode}
+          onChange={(e) => setSetting("hotkey_mode", e.target.value)}
+          className="input-branded mt-3"
+          style={inputStyle}
+        >
+          <option value="hold">{t("settings.hotkeyModeHold")}</option>
+          <option value="toggle">{t("settings.hotkeyModeToggle")}</option>
+        </select>
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t(hotkeyMode === "toggle" ? "settings.hotkeyModeToggleDesc" : "settings.hotkeyModeHoldDesc")}
+        </p>
+      </section>
+
+      {/* Command Mode */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <WandSparkles className="w-4 h-4" style={{ color: "var(--color-brand-cyan)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.commandHotkey")}</h2>
+        </div>
+        <HotkeyField action="command" value={commandHotkey} placeholder="Ctrl+Shift+E" inputStyle={inputStyle} />
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.commandHotkeyDesc")}
+        </p>
+      </section>
+
+      {/* Translation Mode */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <Languages className="w-4 h-4" style={{ color: "var(--color-success)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.translation")}</h2>
+        </div>
+        <HotkeyField action="translate" value={translateHotkey} placeholder="Ctrl+Shift+T" inputStyle={inputStyle} />
+        <select
+          value={outputLanguage}
+          onChange={(e) => setSetting("output_language", e.target.value)}
+          className="input-branded mt-3"
+          style={inputStyle}
+        >
+          {["en", "pt", "es", "fr", "de", "it", "ja", "ko", "zh"].map((code) => (
+            <option key={code} value={code}>
+              {t(`settings.languages.${code}`)}
+            </option>
+          ))}
+        </select>
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.translationDesc")}
+        </p>
+      </section>
+
+      {/* Local API */}
+      <section style={cardStyle}>
+        <div className="flex items-center gap-2 mb-3">
+          <Plug className="w-4 h-4" style={{ color: "var(--color-brand-light)" }} />
+          <h2 className="font-semibold text-sm">{t("settings.localApi")}</h2>
+        </div>
+        <LocalApi inputStyle={inputStyle} />
+        <p className="text-xs mt-2" style={{ color: "var(--color-text-muted)" }}>
+          {t("settings.localApiDesc")}
+        </p>
       </section>
 
       {/* Toggles */}
@@ -275,6 +712,38 @@ export default function Settings() {
             activeGradient="linear-gradient(135deg, #F59E0B 0%, #FBBF24 100%)"
           />
         </div>
+
+        {rawMode && (
+          <div className="flex items-center justify-between">
+            <div className="flex items-center gap-2">
+              <Type className="w-4 h-4" style={{ color: "var(--color-warning)" }} />
+              <div>
+                <span className="font-semibold text-sm">{t("settings.spokenCommands")}</span>
+                <p className="text-xs mt-0.5" style={{ color: "var(--color-text-muted)" }}>
+                  {t("settings.spokenCommandsDesc")}
+                </p>
+              </div>
+            </div>
+            <Toggle
+              checked={spokenCommands}
+              onChange={() => setSetting("spoken_commands", String(!spokenCommands))}
+              activeGradient="linear-gradient(135deg, #F59E0B 0%, #FBBF24 100%)"
+            />
+          </div>
+        )}
+
+        <div className="flex items-center justify-between">
+          <div className="flex items-center gap-2">
+            <Scissors className="w-4 h-4" style={{ color: "var(--color-brand-light)" }} />
+            <div>
+              <span className="font-semibold text-sm">{t("settings.vad")}</span>
+              <p className="text-xs mt-0.5" style={{ color: "var(--color-text-muted)" }}>
+                {t("settings.vadDesc")}
+              </p>
+            </div>
+          </div>
+          <Toggle checked={vad} onChange={() => setSetting("vad", String(!vad))} />
+        </div>
       </section>
     </div>
   );
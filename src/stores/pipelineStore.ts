@@ -6,7 +6,8 @@ export type PipelineState =
   | "encoding"
   | "transcribing"
   | "refining"
-  | "injecting";
+  | "injecting"
+  | "cancelled";
 
 interface PipelineResult {
   raw_text: string;
@@ -1,5 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
+import type { HotkeyAction } from "../types";
 
 interface SettingsState {
   apiKey: string;
@@ -8,13 +9,33 @@ interface SettingsState {
   language: string;
   uiLanguage: string;
   hotkey: string;
+  hotkeyMode: string;
   darkMode: boolean;
   autostart: boolean;
   rawMode: boolean;
+  vad: boolean;
+  inputDevice: string;
+  uploadFormat: string;
+  sttProvider: string;
+  openaiBaseUrl: string;
+  openaiSttModel: string;
+  deepgramModel: string;
+  whisperModelPath: string;
+  refineProvider: string;
+  refineOpenaiBaseUrl: string;
+  refineOpenaiModel: string;
+  ollamaBaseUrl: string;
+  ollamaModel: string;
+  spokenCommands: boolean;
+  promptProfile: string;
+  commandHotkey: string;
+  translateHotkey: string;
+  outputLanguage: string;
   loading: boolean;
 
   loadSettings: () => Promise<void>;
   setApiKey: (key: string) => Promise<void>;
+  setHotkey: (hotkey: string, action?: HotkeyAction) => Promise<void>;
   setSetting: (key: string, value: string) => Promise<void>;
   toggleDarkMode: () => void;
 }
@@ -26,9 +47,28 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   language: "pt",
   uiLanguage: "en",
   hotkey: "Ctrl+Shift+Space",
+  hotkeyMode: "hold",
   darkMode: false,
   autostart: false,
   rawMode: false,
+  vad: true,
+  inputDevice: "",
+  uploadFormat: "wav",
+  sttProvider: "groq",
+  openaiBaseUrl: "",
+  openaiSttModel: "",
+  deepgramModel: "",
+  whisperModelPath: "",
+  refineProvider: "groq",
+  refineOpenaiBaseUrl: "",
+  refineOpenaiModel: "",
+  ollamaBaseUrl: "",
+  ollamaModel: "",
+  spokenCommands: true,
+  promptProfile: "default",
+  commandHotkey: "",
+  translateHotkey: "",
+  outputLanguage: "en",
   loading: true,
 
   loadSettings: async () => {
@@ -39,9 +79,28 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
         "language",
         "ui_language",
         "hotkey",
+        "hotkey_mode",
         "dark_mode",
         "autostart",
         "raw_mode",
+        "vad",
+        "input_device",
+        "upload_format",
+        "stt_provider",
+        "openai_base_url",
+        "openai_stt_model",
+        "deepgram_model",
+        "whisper_model_path",
+        "refine_provider",
+        "refine_openai_base_url",
+        "refine_openai_model",
+        "ollama_base_url",
+        "ollama_model",
+        "spoken_commands",
+        "prompt_profile",
+        "command_hotkey",
+        "translate_hotkey",
+        "output_language",
       ];
 
       const values = await Promise.all(
@@ -66,9 +125,28 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
         language: results.language || "pt",
         uiLanguage: results.ui_language || "en",
         hotkey: results.hotkey || "Ctrl+Shift+Space",
+        hotkeyMode: results.hotkey_mode || "hold",
         darkMode: results.dark_mode === "true",
         autostart: results.autostart === "true",
         rawMode: results.raw_mode === "true",
+        vad: results.vad !== "false",
+        inputDevice: results.input_device || "",
+        uploadFormat: results.upload_format || "wav",
+        sttProvider: results.stt_provider || "groq",
+        openaiBaseUrl: results.openai_base_url || "",
+        openaiSttModel: results.openai_stt_model || "",
+        deepgramModel: results.deepgram_model || "",
+        whisperModelPath: results.whisper_model_path || "",
+        refineProvider: results.refine_provider || "groq",
+        refineOpenaiBaseUrl: results.refine_openai_base_url || "",
+        refineOpenaiModel: results.refine_openai_model || "",
+        ollamaBaseUrl: results.ollama_base_url || "",
+        ollamaModel: results.ollama_model || "",
+        spokenCommands: results.spoken_commands !== "false",
+        promptProfile: results.prompt_profile || "default",
+        commandHotkey: results.command_hotkey || "",
+        translateHotkey: results.translate_hotkey || "",
+        outputLanguage: results.output_language || "en",
         loading: false,
       });
     } catch (err) {
@@ -82,11 +160,18 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     set({ apiKey: key });
   },
 
+  setHotkey: async (hotkey: string, action: HotkeyAction = "dictate") => {
+    // Re-registers the global shortcut and persists it; rejects with a HotkeyError
+    await invoke("set_hotkey", { hotkey, action });
+    const field = { dictate: "hotkey", command: "commandHotkey", translate: "translateHotkey" } as const;
+    set({ [field[action]]: hotkey });
+  },
+
   setSetting: async (key: string, value: string) => {
     await invoke("set_setting", { key, value });
 
-    type BooleanField = "darkMode" | "autostart" | "rawMode";
-    type SettingField = BooleanField | "sttModel" | "llmModel" | "language" | "uiLanguage" | "hotkey";
+    type BooleanField = "darkMode" | "autostart" | "rawMode" | "vad" | "spokenCommands";
+    type SettingField = BooleanField | "sttModel" | "llmModel" | "language" | "uiLanguage" | "hotkey" | "hotkeyMode" | "inputDevice" | "uploadFormat" | "sttProvider" | "openaiBaseUrl" | "openaiSttModel" | "deepgramModel" | "whisperModelPath" | "refineProvider" | "refineOpenaiBaseUrl" | "refineOpenaiModel" | "ollamaBaseUrl" | "ollamaModel" | "promptProfile" | "commandHotkey" | "translateHotkey" | "outputLanguage";
 
     const BACKEND_TO_STATE: Record<string, SettingField> = {
       stt_model: "sttModel",
@@ -94,11 +179,30 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       language: "language",
       ui_language: "uiLanguage",
       hotkey: "hotkey",
+      hotkey_mode: "hotkeyMode",
       dark_mode: "darkMode",
       autostart: "autostart",
       raw_mode: "rawMode",
+      vad: "vad",
+      input_device: "inputDevice",
+      upload_format: "uploadFormat",
+      stt_provider: "sttProvider",
+      openai_base_url: "openaiBaseUrl",
+      openai_stt_model: "openaiSttModel",
+      deepgram_model: "deepgramModel",
+      whisper_model_path: "whisperModelPath",
+      refine_provider: "refineProvider",
+      refine_openai_base_url: "refineOpenaiBaseUrl",
+      refine_openai_model: "refineOpenaiModel",
+      ollama_base_url: "ollamaBaseUrl",
+      ollama_model: "ollamaModel",
+      spoken_commands: "spokenCommands",
+      prompt_profile: "promptProfile",
+      command_hotkey: "commandHotkey",
+      translate_hotkey: "translateHotkey",
+      output_language: "outputLanguage",
     };
-    const BOOLEAN_FIELDS = new Set<BooleanField>(["darkMode", "autostart", "rawMode"]);
+    const BOOLEAN_FIELDS = new Set<BooleanField>(["darkMode", "autostart", "rawMode", "vad", "spokenCommands"]);
 
     const field = BACKEND_TO_STATE[key];
     if (field) {
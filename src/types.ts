@@ -6,6 +6,8 @@ export interface Transcription {
   llm_latency_ms: number;
   word_count: number;
   created_at: string;
+  app: string | null;
+  language: string | null;
 }
 
 export interface TranscriptionStats {
@@ -16,4 +18,100 @@ export interface TranscriptionStats {
   words_this_month: number;
   avg_stt_latency_ms: number;
   avg_llm_latency_ms: number;
+  languages: LanguageStats[];
+}
+
+export interface WordTiming {
+  start_ms: number;
+  end_ms: number;
+  word: string;
+  confidence: number | null;
+}
+
+export interface Segment {
+  start_ms: number;
+  end_ms: number;
+  text: string;
+  confidence: number | null;
+  words: WordTiming[];
+}
+
+export interface LanguageStats {
+  language: string;
+  transcriptions: number;
+  words: number;
+}
+
+export interface InputConfig {
+  channels: number;
+  min_sample_rate: number;
+  max_sample_rate: number;
+  sample_format: string;
+}
+
+export interface InputDevice {
+  name: string;
+  is_default: boolean;
+  configs: InputConfig[];
+}
+
+export type HotkeyAction = "dictate" | "command" | "translate";
+
+export interface HotkeyError {
+  kind: "invalid" | "rejected";
+  hotkey: string;
+  reason: string;
+}
+
+export interface VocabularyTerm {
+  id: string;
+  term: string;
+  created_at: string;
+}
+
+export type RuleKind = "plain" | "whole_word" | "regex";
+
+export interface ReplacementRule {
+  id: string;
+  pattern: string;
+  replacement: string;
+  kind: RuleKind;
+  case_insensitive: boolean;
+  enabled: boolean;
+  position: number;
+  created_at: string;
+}
+
+export interface PromptProfile {
+  id: string;
+  name: string;
+  system_prompt: string;
+  temperature: number;
+  model: string | null;
+  builtin: boolean;
+  created_at: string;
+}
+
+export interface AppRule {
+  id: string;
+  window_class: string;
+  window_title: string;
+  profile_id: string | null;
+  language: string | null;
+  injection: string | null;
+  enabled: boolean;
+  position: number;
+  created_at: string;
+}
+
+export interface ActiveWindow {
+  class: string;
+  title: string;
+}
+
+export interface ApiServerStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  token: string;
 }